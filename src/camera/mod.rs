@@ -1,5 +1,6 @@
 use crate::ray::*;
 use crate::rtweekend::*;
+use crate::sampler::Sampler;
 use crate::vec3::*;
 
 pub struct Camera {
@@ -9,11 +10,14 @@ pub struct Camera {
     vertical: Vec3,
     u: Vec3,
     v: Vec3,
-    w: Vec3,
     lens_radius: f32,
+    /// Rays are spread uniformly over the time the shutter is open.
+    shutter_open: f32,
+    shutter_close: f32,
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         lookfrom: Point3,
         lookat: Point3,
@@ -22,6 +26,8 @@ impl Camera {
         aspect_ratio: f32,
         aperture: f32,
         focus_dist: f32,
+        shutter_open: f32,
+        shutter_close: f32,
     ) -> Self {
         let theta: f32 = degree_to_radians(vfov);
         let h: f32 = (theta / 2.0).tan();
@@ -39,20 +45,26 @@ impl Camera {
             lens_radius: aperture / 2.0,
             u,
             v,
-            w,
             origin,
             horizonal,
             vertical,
             lower_left_corner: origin - horizonal / 2.0 - vertical / 2.0 - w * focus_dist,
+            shutter_open,
+            shutter_close,
         }
     }
 
-    pub fn get_ray(&self, u: f32, v: f32) -> Ray {
-        let rd: Vec3 = self.lens_radius * Vec3::random_in_unit_disk();
+    /// The ray through viewport coordinates `(u, v)`, from a point on the
+    /// lens at a time while the shutter is open, both drawn from `sampler`.
+    pub fn get_ray(&self, u: f32, v: f32, sampler: &mut dyn Sampler) -> Ray {
+        let rd: Vec3 = self.lens_radius * Vec3::sample_in_unit_disk(sampler.get_2d());
         let offset: Vec3 = self.u * rd.x() + self.v * rd.y();
-        Ray::new(
+        let time: f32 =
+            self.shutter_open + sampler.get_1d() * (self.shutter_close - self.shutter_open);
+        Ray::with_time(
             self.origin + offset,
             self.lower_left_corner + u * self.horizonal + v * self.vertical - self.origin - offset,
+            time,
         )
     }
 }
@@ -0,0 +1,20 @@
+use super::*;
+
+/// The same color in every direction; black leaves emissive materials as the
+/// only light.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstantEnvironment {
+    color: Color,
+}
+
+impl ConstantEnvironment {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+}
+
+impl Environment for ConstantEnvironment {
+    fn color(&self, _direction: &Vec3) -> Color {
+        self.color
+    }
+}
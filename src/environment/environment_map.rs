@@ -0,0 +1,204 @@
+use super::*;
+
+use std::io;
+use std::path::Path;
+
+/// An equirectangular (latitude-longitude) radiance image wrapped around the
+/// scene: the top row is straight up, the bottom row straight down, and the
+/// middle of the image lies along -z. Each pixel is picked by `random` in
+/// proportion to its luminance, so a small bright sun is found by aiming at
+/// it rather than by chance.
+pub struct EnvironmentMap {
+    image: Framebuffer,
+    /// Turn about the vertical axis, in radians.
+    rotation: f32,
+    intensity: f32,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    /// Wraps `image` around the scene turned by `rotation` degrees about the
+    /// vertical axis, with its radiance scaled by `intensity`.
+    pub fn new(image: Framebuffer, rotation: f32, intensity: f32) -> Self {
+        assert!(
+            image.width() > 0 && image.height() > 0,
+            "EnvironmentMap needs at least one pixel."
+        );
+        assert!(
+            intensity >= 0.0,
+            "EnvironmentMap intensity must be non-negative."
+        );
+        let (width, height) = (image.width(), image.height());
+        let pixels: &Framebuffer = &image;
+        // Rows near the poles cover less of the sphere than those at the equator.
+        let weights: Vec<f32> = (0..height)
+            .flat_map(|y| {
+                let sin_theta: f32 = (PI * (y as f32 + 0.5) / height as f32).sin();
+                (0..width).map(move |x| pixels.get(x, y).luminance().max(0.0) * sin_theta)
+            })
+            .collect();
+        let distribution: Distribution2D = Distribution2D::new(&weights, width, height);
+        Self {
+            image,
+            rotation: degree_to_radians(rotation),
+            intensity,
+            distribution,
+        }
+    }
+
+    /// Loads an HDR, EXR or any other image file with `image::load`. Pixels
+    /// that overflowed to infinity or are NaN, as half-float EXR suns can, are
+    /// rejected rather than sampled.
+    pub fn load(path: &Path, rotation: f32, intensity: f32) -> io::Result<Self> {
+        let image: Framebuffer = image::load(path)?;
+        if image.width() == 0 || image.height() == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "image has no pixels",
+            ));
+        }
+        if let Some(i) = image
+            .pixels()
+            .iter()
+            .position(|c| !(c.x().is_finite() && c.y().is_finite() && c.z().is_finite()))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "pixel ({}, {}) is infinite or NaN",
+                    i % image.width(),
+                    i / image.width()
+                ),
+            ));
+        }
+        Ok(EnvironmentMap::new(image, rotation, intensity))
+    }
+
+    /// Image coordinates in [0, 1) of `direction`, with v = 0 at the top row.
+    fn to_uv(&self, direction: &Vec3) -> (f32, f32) {
+        let d: Vec3 = direction.unit_vector();
+        // atan2 keeps its precision near the poles, where acos rounds to 0 or π.
+        let theta: f32 = (d.x() * d.x() + d.z() * d.z()).sqrt().atan2(d.y());
+        let phi: f32 = d.x().atan2(-d.z()) - self.rotation;
+        ((phi / (2.0 * PI) + 0.5).rem_euclid(1.0), theta / PI)
+    }
+
+    fn direction(&self, u: f32, v: f32) -> Vec3 {
+        let theta: f32 = PI * v;
+        let phi: f32 = 2.0 * PI * (u - 0.5) + self.rotation;
+        Vec3::new(
+            theta.sin() * phi.sin(),
+            theta.cos(),
+            -theta.sin() * phi.cos(),
+        )
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn color(&self, direction: &Vec3) -> Color {
+        let (u, v) = self.to_uv(direction);
+        let x: usize = ((u * self.image.width() as f32) as usize).min(self.image.width() - 1);
+        let y: usize = ((v * self.image.height() as f32) as usize).min(self.image.height() - 1);
+        self.intensity * self.image.get(x, y)
+    }
+
+    fn pdf_value(&self, direction: &Vec3) -> f32 {
+        let (u, v) = self.to_uv(direction);
+        let d: Vec3 = direction.unit_vector();
+        let sin_theta: f32 = (d.x() * d.x() + d.z() * d.z()).sqrt();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        // The image covers 2π by π radians, squeezed by sin θ towards the poles.
+        self.distribution.value(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+
+    fn random(&self, u: (f32, f32)) -> Vec3 {
+        let (x, y, _) = self.distribution.sample(u.0, u.1);
+        self.direction(x, y)
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dim 8x4 map with one bright pixel just above the horizon, left of
+    /// centre.
+    fn sun_map(rotation: f32) -> EnvironmentMap {
+        let mut image: Framebuffer =
+            Framebuffer::from_pixels(8, 4, vec![Color::new(0.1, 0.1, 0.1); 32]);
+        image.set(3, 1, Color::new(100.0, 100.0, 100.0));
+        EnvironmentMap::new(image, rotation, 2.0)
+    }
+
+    #[test]
+    fn test_direction_round_trip() {
+        let map: EnvironmentMap = sun_map(30.0);
+        for (u, v) in [(0.1, 0.2), (0.5, 0.5), (0.9, 0.75)] {
+            let (u2, v2) = map.to_uv(&map.direction(u, v));
+            assert!((u - u2).abs() < 1e-5 && (v - v2).abs() < 1e-5);
+        }
+        // The middle of an unrotated map is straight ahead.
+        let map: EnvironmentMap = sun_map(0.0);
+        assert_eq!(map.to_uv(&Vec3::new(0.0, 0.0, -2.0)), (0.5, 0.5));
+        assert_eq!(
+            map.color(&Vec3::new(0.0, 1.0, 0.0)),
+            Color::new(0.2, 0.2, 0.2)
+        );
+    }
+
+    #[test]
+    fn test_sampling_favours_bright_pixels() {
+        let map: EnvironmentMap = sun_map(90.0);
+        let mut rng: Rng = Rng::new(7);
+        let trials: usize = 100000;
+        let mut bright: usize = 0;
+        let mut total_weight: f32 = 0.0;
+        for _ in 0..trials {
+            let direction: Vec3 = map.random((rng.random_double(), rng.random_double()));
+            let pdf: f32 = map.pdf_value(&direction);
+            assert!(pdf > 0.0);
+            if map.color(&direction).x() > 1.0 {
+                bright += 1;
+            }
+            total_weight += 1.0 / pdf;
+        }
+        assert!(bright as f32 / trials as f32 > 0.9);
+        // The importance weights estimate the sphere's solid angle.
+        assert!((total_weight / trials as f32 / (4.0 * PI) - 1.0).abs() < 0.1);
+
+        // Uniformly chosen directions see the density integrate to 1.
+        let n: usize = 200000;
+        let integral: f32 = (0..n)
+            .map(|_| map.pdf_value(&Vec3::random_unit_vector(&mut rng)))
+            .sum::<f32>()
+            * 4.0
+            * PI
+            / n as f32;
+        assert!((integral - 1.0).abs() < 0.05, "{}", integral);
+    }
+
+    #[test]
+    fn test_load_rejects_overflowed_pixels() {
+        use crate::image::{save, ImageFormat};
+
+        // 1e6 is past the largest half float, so the sun is stored as infinity.
+        let mut sky: Framebuffer =
+            Framebuffer::from_pixels(4, 2, vec![Color::new(0.5, 0.5, 0.5); 8]);
+        sky.set(2, 1, Color::new(1e6, 1e6, 1e6));
+        let path: std::path::PathBuf =
+            std::env::temp_dir().join(format!("overflowed_sun_{}.exr", std::process::id()));
+        save(&sky, &path, ImageFormat::ExrHalf).unwrap();
+        let result: io::Result<EnvironmentMap> = EnvironmentMap::load(&path, 0.0, 1.0);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "pixel (2, 1) is infinite or NaN"
+        );
+    }
+}
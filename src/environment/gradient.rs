@@ -0,0 +1,32 @@
+use super::*;
+
+/// A vertical blend from `bottom`, straight down, to `top`, straight up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientEnvironment {
+    bottom: Color,
+    top: Color,
+}
+
+impl GradientEnvironment {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        Self { bottom, top }
+    }
+
+    /// The white-to-blue sky of the book's outdoor scenes.
+    pub fn sky() -> Self {
+        GradientEnvironment::new(Color::new(1.0, 1.0, 1.0), Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Default for GradientEnvironment {
+    fn default() -> Self {
+        GradientEnvironment::sky()
+    }
+}
+
+impl Environment for GradientEnvironment {
+    fn color(&self, direction: &Vec3) -> Color {
+        let t: f32 = 0.5 * (direction.unit_vector().y() + 1.0);
+        (1.0 - t) * self.bottom + t * self.top
+    }
+}
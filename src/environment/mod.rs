@@ -0,0 +1,34 @@
+use crate::image::{self, Framebuffer};
+use crate::pdf::Distribution2D;
+use crate::rtweekend::*;
+use crate::vec3::*;
+
+/// The light arriving from infinitely far away: what a ray sees when it
+/// leaves the scene without hitting anything.
+pub trait Environment: Send + Sync {
+    /// Radiance arriving from `direction`, which need not be normalized.
+    fn color(&self, direction: &Vec3) -> Color;
+
+    /// Solid-angle density with which `random` picks `direction`. Only
+    /// environments bright enough in places to be worth aiming at override it.
+    fn pdf_value(&self, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    /// The direction towards the environment that `u`, a point of the unit
+    /// square, maps to; uniform `u` gives directions distributed by
+    /// `pdf_value`.
+    fn random(&self, _u: (f32, f32)) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+}
+
+pub mod constant;
+pub mod environment_map;
+pub mod gradient;
+pub mod physical_sky;
+
+pub use constant::ConstantEnvironment;
+pub use environment_map::EnvironmentMap;
+pub use gradient::GradientEnvironment;
+pub use physical_sky::{PhysicalSky, SUN_RADIUS};
@@ -0,0 +1,290 @@
+use super::*;
+
+/// The sun's apparent radius from the ground, in degrees.
+pub const SUN_RADIUS: f32 = 0.27;
+
+/// Radiance per kcd/m² of sky luminance, so that sun and sky light a scene
+/// about as brightly as the book's gradient does.
+const SKY_SCALE: f32 = 0.05;
+/// Illuminance from the sun above the atmosphere, in the same units.
+const SUN_ILLUMINANCE: f32 = 6.0;
+/// Share of `random`'s samples aimed at the sun rather than the open sky.
+const SUN_PROBABILITY: f32 = 0.5;
+
+/// A clear daylight sky after Preetham, Shirley and Smits, "A Practical
+/// Analytic Model for Daylight" (1999), with the sun as a bright disk of
+/// angular radius `SUN_RADIUS`. `turbidity` measures haze, from 2 for a
+/// crisp mountain sky to 10 for a murky one; it whitens the sky and reddens
+/// the sun as it sinks.
+///
+/// Below the horizon the sky is black: the scene is expected to supply the
+/// ground. Half of `random`'s samples aim at the sun, so sunlight and shadows
+/// converge as quickly as a light in the scene would.
+pub struct PhysicalSky {
+    sun_direction: Vec3,
+    turbidity: f32,
+    cos_sun_radius: f32,
+    sun_radiance: Color,
+    /// Perez coefficients A to E for luminance Y and chromaticities x and y.
+    perez: [[f32; 5]; 3],
+    /// Y, x and y at the zenith, divided by the Perez function there.
+    zenith: [f32; 3],
+    intensity: f32,
+}
+
+impl PhysicalSky {
+    /// A sky with the sun `elevation` degrees above the horizon and
+    /// `azimuth` degrees from -z towards +x.
+    pub fn new(elevation: f32, azimuth: f32, turbidity: f32) -> Self {
+        assert!(
+            (0.0..=90.0).contains(&elevation),
+            "PhysicalSky sun elevation must lie between 0 and 90 degrees."
+        );
+        assert!(
+            (2.0..=10.0).contains(&turbidity),
+            "PhysicalSky turbidity must lie between 2 and 10."
+        );
+        let (elevation, azimuth) = (degree_to_radians(elevation), degree_to_radians(azimuth));
+        let sun_direction: Vec3 = Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            -elevation.cos() * azimuth.cos(),
+        );
+        let t: f32 = turbidity;
+        let perez: [[f32; 5]; 3] = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let theta_s: f32 = PI / 2.0 - elevation;
+        let chi: f32 = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let luminance: f32 = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let chromaticity = |m: [[f32; 4]; 3]| -> f32 {
+            let angles: [f32; 4] = [theta_s.powi(3), theta_s.powi(2), theta_s, 1.0];
+            let row = |r: [f32; 4]| -> f32 { r.iter().zip(angles).map(|(a, b)| a * b).sum() };
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+        let x: f32 = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let y: f32 = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        let mut zenith: [f32; 3] = [luminance, x, y];
+        for (value, coefficients) in zenith.iter_mut().zip(perez) {
+            *value /= perez_function(&coefficients, 1.0, theta_s);
+        }
+
+        let mut sky: PhysicalSky = Self {
+            sun_direction,
+            turbidity,
+            cos_sun_radius: 1.0,
+            sun_radiance: Color::new(0.0, 0.0, 0.0),
+            perez,
+            zenith,
+            intensity: 1.0,
+        };
+        sky.set_sun(SUN_RADIUS);
+        sky
+    }
+
+    /// Widens the sun to a disk of angular radius `radius` degrees. It lights
+    /// the scene just as strongly, but casts softer shadows.
+    pub fn with_sun_radius(mut self, radius: f32) -> Self {
+        assert!(
+            radius > 0.0 && radius < 90.0,
+            "PhysicalSky sun radius must lie between 0 and 90 degrees."
+        );
+        self.set_sun(radius);
+        self
+    }
+
+    /// Scales both sky and sun by `intensity`.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        assert!(
+            intensity >= 0.0,
+            "PhysicalSky intensity must be non-negative."
+        );
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    /// Spreads the sun's light, dimmed and reddened by the air it crosses,
+    /// over a disk of `radius` degrees.
+    fn set_sun(&mut self, radius: f32) {
+        self.cos_sun_radius = degree_to_radians(radius).cos();
+        let zenith_angle: f32 = self.sun_direction.y().clamp(0.0, 1.0).acos();
+        // Relative length of the path through the air, after Kasten (1966).
+        let air_mass: f32 =
+            1.0 / (zenith_angle.cos() + 0.15 * (93.885 - zenith_angle.to_degrees()).powf(-1.253));
+        let beta: f32 = 0.04608 * self.turbidity - 0.04586;
+        // Rayleigh scattering by the air and Ångström's law for the haze, at
+        // red, green and blue wavelengths in micrometres.
+        let transmittance = |lambda: f32| -> f32 {
+            (-air_mass * (0.008735 * lambda.powf(-4.08) + beta * lambda.powf(-1.3))).exp()
+        };
+        self.sun_radiance = SUN_ILLUMINANCE / self.sun_solid_angle()
+            * Color::new(
+                transmittance(0.65),
+                transmittance(0.55),
+                transmittance(0.45),
+            );
+    }
+
+    /// Radiance of the open sky in the unit direction `d`, above the horizon.
+    fn sky(&self, d: &Vec3) -> Color {
+        let cos_theta: f32 = d.y().max(0.001);
+        let gamma: f32 = d.dot(&self.sun_direction).clamp(-1.0, 1.0).acos();
+        let [luminance, x, y] =
+            [0, 1, 2].map(|i| self.zenith[i] * perez_function(&self.perez[i], cos_theta, gamma));
+        // From xyY chromaticity and luminance through CIE XYZ to linear sRGB.
+        let big_x: f32 = x / y * luminance;
+        let big_z: f32 = (1.0 - x - y) / y * luminance;
+        let rgb: Color = Color::new(
+            3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z,
+            -0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z,
+            0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z,
+        );
+        SKY_SCALE * Color::new(rgb.x().max(0.0), rgb.y().max(0.0), rgb.z().max(0.0))
+    }
+
+    fn sun_solid_angle(&self) -> f32 {
+        2.0 * PI * (1.0 - self.cos_sun_radius)
+    }
+}
+
+/// Perez et al.'s sky distribution for a direction `acos(cos_theta)` from the
+/// zenith and `gamma` from the sun.
+fn perez_function(c: &[f32; 5], cos_theta: f32, gamma: f32) -> f32 {
+    (1.0 + c[0] * (c[1] / cos_theta).exp())
+        * (1.0 + c[2] * (c[3] * gamma).exp() + c[4] * gamma.cos() * gamma.cos())
+}
+
+impl Environment for PhysicalSky {
+    fn color(&self, direction: &Vec3) -> Color {
+        let d: Vec3 = direction.unit_vector();
+        if d.y() <= 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let mut color: Color = self.sky(&d);
+        if d.dot(&self.sun_direction) >= self.cos_sun_radius {
+            color += self.sun_radiance;
+        }
+        self.intensity * color
+    }
+
+    fn pdf_value(&self, direction: &Vec3) -> f32 {
+        let d: Vec3 = direction.unit_vector();
+        let mut pdf: f32 = 0.0;
+        if d.y() > 0.0 {
+            pdf += (1.0 - SUN_PROBABILITY) / (2.0 * PI);
+        }
+        if d.dot(&self.sun_direction) >= self.cos_sun_radius {
+            pdf += SUN_PROBABILITY / self.sun_solid_angle();
+        }
+        pdf
+    }
+
+    fn random(&self, u: (f32, f32)) -> Vec3 {
+        // Whichever share of `u.0` picks the sun or the sky is stretched back
+        // over [0, 1).
+        if u.0 < SUN_PROBABILITY {
+            let u0: f32 = u.0 / SUN_PROBABILITY;
+            Onb::build_from_w(&self.sun_direction)
+                .local(&Vec3::sample_in_cone(self.cos_sun_radius, (u0, u.1)))
+        } else {
+            let u0: f32 = (u.0 - SUN_PROBABILITY) / (1.0 - SUN_PROBABILITY);
+            Onb::build_from_w(&Vec3::new(0.0, 1.0, 0.0))
+                .local(&Vec3::sample_in_cone(0.0, (u0, u.1)))
+        }
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sky_colors() {
+        let sky: PhysicalSky = PhysicalSky::new(60.0, 90.0, 3.0);
+        assert!((sky.sun_direction() - Vec3::new(0.5, 0.866, 0.0)).length() < 1e-3);
+        let zenith: Color = sky.color(&Vec3::new(0.0, 1.0, 0.0));
+        assert!(zenith.z() > zenith.x() && zenith.luminance() > 0.1 && zenith.luminance() < 2.0);
+        assert!(sky.color(&sky.sun_direction()).luminance() > 1e4 * zenith.luminance());
+        assert_eq!(
+            sky.color(&Vec3::new(0.0, -1.0, 0.0)),
+            Color::new(0.0, 0.0, 0.0)
+        );
+
+        // A low sun is dimmer and redder.
+        let sunset: PhysicalSky = PhysicalSky::new(2.0, 90.0, 3.0);
+        let (high, low) = (sky.sun_radiance, sunset.sun_radiance);
+        assert!(low.x() < high.x() && low.z() / low.x() < high.z() / high.x());
+
+        // A bigger sun is dimmer but delivers the same light.
+        let big: PhysicalSky = PhysicalSky::new(60.0, 90.0, 3.0).with_sun_radius(5.0);
+        let irradiance = |s: &PhysicalSky| s.sun_radiance.y() * s.sun_solid_angle();
+        assert!((irradiance(&big) / irradiance(&sky) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sampling_finds_the_sun() {
+        let sky: PhysicalSky = PhysicalSky::new(30.0, -45.0, 4.0).with_sun_radius(10.0);
+        let mut rng: Rng = Rng::new(7);
+        let trials: usize = 10000;
+        let mut on_sun: usize = 0;
+        let mut mean: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+        for _ in 0..trials {
+            let direction: Vec3 = sky.random((rng.random_double(), rng.random_double()));
+            assert!(sky.pdf_value(&direction) > 0.0);
+            if direction.unit_vector().dot(&sky.sun_direction()) >= sky.cos_sun_radius {
+                on_sun += 1;
+            } else {
+                mean += direction.unit_vector() / trials as f32;
+            }
+        }
+        assert!((on_sun as f32 / trials as f32 - 0.5).abs() < 0.03);
+        // The open sky is covered evenly, so only the vertical average remains.
+        assert!(mean.x().abs() < 0.02 && mean.z().abs() < 0.02);
+
+        let n: usize = 400000;
+        let integral: f32 = (0..n)
+            .map(|_| sky.pdf_value(&Vec3::random_unit_vector(&mut rng)))
+            .sum::<f32>()
+            * 4.0
+            * PI
+            / n as f32;
+        assert!((integral - 1.0).abs() < 0.05, "{}", integral);
+    }
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+use std::io::Cursor;
+
+use ::exr::prelude::{f16, Image, ReadChannels, ReadLayers, SpecificChannels, Vec2, WritableImage};
+
+/// OpenEXR writer for the raw linear framebuffer, with RGB channels stored as
+/// half or full precision floats.
+pub struct ExrWriter {
+    pub half: bool,
+}
+
+impl ImageWriter for ExrWriter {
+    fn write(&self, fb: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        let size: (usize, usize) = (fb.width(), fb.height());
+        // EXR needs a seekable stream to fill in its offset table afterwards.
+        let mut buffer: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+        let result = if self.half {
+            let channels = SpecificChannels::rgb(|Vec2(x, y): Vec2<usize>| {
+                let c: Color = fb.get(x, y);
+                (
+                    f16::from_f32(c.x()),
+                    f16::from_f32(c.y()),
+                    f16::from_f32(c.z()),
+                )
+            });
+            Image::from_channels(size, channels)
+                .write()
+                .to_buffered(&mut buffer)
+        } else {
+            let channels = SpecificChannels::rgb(|Vec2(x, y): Vec2<usize>| {
+                let c: Color = fb.get(x, y);
+                (c.x(), c.y(), c.z())
+            });
+            Image::from_channels(size, channels)
+                .write()
+                .to_buffered(&mut buffer)
+        };
+        result.map_err(io::Error::other)?;
+        out.write_all(buffer.get_ref())
+    }
+}
+
+/// Reads the RGB channels of the first layer of an OpenEXR image, at full
+/// resolution.
+pub fn read_exr<R: Read>(mut input: R) -> io::Result<Framebuffer> {
+    let mut bytes: Vec<u8> = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    let image = ::exr::prelude::read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .rgb_channels(
+            |size: Vec2<usize>, _| Framebuffer::new(size.width(), size.height()),
+            |fb: &mut Framebuffer, pos: Vec2<usize>, (r, g, b): (f32, f32, f32)| {
+                fb.set(pos.x(), pos.y(), Color::new(r, g, b))
+            },
+        )
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(Cursor::new(bytes))
+        .map_err(|err| invalid_data(err.to_string()))?;
+    Ok(image.layer_data.channel_data.pixels)
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_hdr_values() {
+        let mut fb: Framebuffer = Framebuffer::new(2, 1);
+        fb.set(0, 0, Color::new(4.5, 0.25, 1000.0));
+        fb.set(1, 0, Color::new(0.0, 1.0, 0.5));
+
+        for half in [false, true] {
+            let mut out: Vec<u8> = Vec::new();
+            ExrWriter { half }.write(&fb, &mut out).unwrap();
+            assert_eq!(read_exr(out.as_slice()).unwrap(), fb);
+        }
+    }
+}
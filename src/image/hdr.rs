@@ -0,0 +1,178 @@
+use super::*;
+
+/// Largest width or height accepted from an HDR header.
+const MAX_DIMENSION: usize = 1 << 16;
+
+/// Reads a Radiance `.hdr` image with flat or run-length encoded scanlines.
+pub fn read_hdr<R: BufRead>(mut input: R) -> io::Result<Framebuffer> {
+    let magic: String = read_line(&mut input)?;
+    if !magic.starts_with("#?") {
+        return Err(invalid_data("not a Radiance HDR image".to_string()));
+    }
+    loop {
+        let line: String = read_line(&mut input)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(invalid_data(format!("unsupported format '{}'", format)));
+            }
+        }
+    }
+
+    let resolution: String = read_line(&mut input)?;
+    let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", h, "+X", w] => match (h.parse::<usize>(), w.parse::<usize>()) {
+            (Ok(h), Ok(w)) => (h, w),
+            _ => return Err(invalid_data(format!("bad resolution '{}'", resolution))),
+        },
+        _ => {
+            return Err(invalid_data(format!(
+                "unsupported orientation '{}'; expected -Y height +X width",
+                resolution
+            )))
+        }
+    };
+
+    if !(1..=MAX_DIMENSION).contains(&width)
+        || !(1..=MAX_DIMENSION).contains(&height)
+        || width.checked_mul(height).is_none()
+    {
+        return Err(invalid_data(format!(
+            "resolution '{}' out of range; sides must be 1 to {} pixels",
+            resolution, MAX_DIMENSION
+        )));
+    }
+
+    // Grown as scanlines arrive, so a truncated file cannot reserve memory
+    // for pixels it does not have.
+    let mut pixels: Vec<Color> = Vec::new();
+    let mut scanline: Vec<u8> = vec![0; width * 4];
+    for _ in 0..height {
+        read_scanline(&mut input, &mut scanline)?;
+        pixels.extend(
+            scanline
+                .chunks_exact(4)
+                .map(|b| from_rgbe([b[0], b[1], b[2], b[3]])),
+        );
+    }
+    Ok(Framebuffer::from_pixels(width, height, pixels))
+}
+
+/// Fills `scanline` with `width` RGBE pixels, interleaved.
+fn read_scanline<R: BufRead>(input: &mut R, scanline: &mut [u8]) -> io::Result<()> {
+    let width: usize = scanline.len() / 4;
+    let mut start: [u8; 4] = [0; 4];
+    input.read_exact(&mut start)?;
+    let encoded: bool = (8..0x8000).contains(&width)
+        && start[0] == 2
+        && start[1] == 2
+        && (start[2] as usize) << 8 | start[3] as usize == width;
+    if !encoded {
+        scanline[..4].copy_from_slice(&start);
+        return input.read_exact(&mut scanline[4..]);
+    }
+
+    // Run-length encoded scanlines store each channel separately.
+    for channel in 0..4 {
+        let mut x: usize = 0;
+        while x < width {
+            let mut count: [u8; 1] = [0];
+            input.read_exact(&mut count)?;
+            let (run, count) = if count[0] > 128 {
+                (true, count[0] as usize - 128)
+            } else {
+                (false, count[0] as usize)
+            };
+            if count == 0 || x + count > width {
+                return Err(invalid_data("corrupt run-length encoding".to_string()));
+            }
+            if run {
+                let mut value: [u8; 1] = [0];
+                input.read_exact(&mut value)?;
+                for i in x..x + count {
+                    scanline[i * 4 + channel] = value[0];
+                }
+            } else {
+                let mut values: Vec<u8> = vec![0; count];
+                input.read_exact(&mut values)?;
+                for (i, value) in (x..x + count).zip(values) {
+                    scanline[i * 4 + channel] = value;
+                }
+            }
+            x += count;
+        }
+    }
+    Ok(())
+}
+
+fn read_line<R: BufRead>(input: &mut R) -> io::Result<String> {
+    let mut line: Vec<u8> = Vec::new();
+    if input.read_until(b'\n', &mut line)? == 0 {
+        return Err(invalid_data("unexpected end of HDR header".to_string()));
+    }
+    Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+fn from_rgbe([r, g, b, e]: [u8; 4]) -> Color {
+    if e == 0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    let scale: f32 = 2f32.powi(e as i32 - 136);
+    Color::new(r as f32 * scale, g as f32 * scale, b as f32 * scale)
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_flat_scanlines() {
+        let mut source: Vec<u8> = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+        source.extend_from_slice(&[250, 2, 0, 138]);
+        source.extend_from_slice(&[32, 128, 96, 129]);
+
+        let read: Framebuffer = read_hdr(source.as_slice()).unwrap();
+        assert_eq!((read.width(), read.height()), (2, 1));
+        // The shared exponent scales every channel of a pixel alike.
+        assert_eq!(read.get(0, 0), Color::new(1000.0, 8.0, 0.0));
+        assert_eq!(read.get(1, 0), Color::new(0.25, 1.0, 0.75));
+    }
+
+    #[test]
+    fn test_read_run_length_encoded() {
+        let mut source: Vec<u8> = b"#?RGBE\nGAMMA=1.0\n\n-Y 1 +X 8\n\x02\x02\x00\x08".to_vec();
+        // Red: a run of eight 128s. Green: eight literal values. Blue: zero.
+        // Exponent: a run of eight 129s, so values are halved.
+        source.extend_from_slice(&[136, 128]);
+        source.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+        source.extend_from_slice(&[136, 0]);
+        source.extend_from_slice(&[136, 129]);
+
+        let read: Framebuffer = read_hdr(source.as_slice()).unwrap();
+        assert_eq!(read.get(0, 0), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(read.get(7, 0), Color::new(1.0, 0.875, 0.0));
+
+        let err: io::Error = read_hdr(&b"#?RADIANCE\n\n+Y 1 +X 1\n"[..]).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "unsupported orientation '+Y 1 +X 1'; expected -Y height +X width"
+        );
+        for resolution in ["-Y 4000000000 +X 4000000000", "-Y 1 +X 0"] {
+            let header: String = format!("#?RADIANCE\n\n{}\n", resolution);
+            let err: io::Error = read_hdr(header.as_bytes()).err().unwrap();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "resolution '{}' out of range; sides must be 1 to 65536 pixels",
+                    resolution
+                )
+            );
+        }
+    }
+}
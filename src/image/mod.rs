@@ -0,0 +1,184 @@
+use crate::vec3::*;
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Unclamped linear radiance for every pixel of the image, stored row by row starting
+/// at the top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::default(); width * height],
+        }
+    }
+
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(pixels.len(), width * height, "One color per pixel.");
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// The image as packed 8-bit sRGB triples, as most formats want it.
+    pub fn to_srgb8(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|c| c.to_srgb8()).collect()
+    }
+}
+
+pub trait ImageWriter {
+    fn write(&self, fb: &Framebuffer, out: &mut dyn Write) -> io::Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    /// Binary portable pixmap (P6).
+    Ppm,
+    /// ASCII portable pixmap (P3).
+    PpmAscii,
+    /// OpenEXR with 32-bit float channels.
+    Exr,
+    /// OpenEXR with 16-bit half float channels.
+    ExrHalf,
+    /// Portable float map.
+    Pfm,
+}
+
+impl ImageFormat {
+    /// Picks the format from a file extension; `.ppm` means binary P6 and
+    /// `.exr` full float precision.
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let extension: String = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            "exr" => Some(ImageFormat::Exr),
+            "pfm" => Some(ImageFormat::Pfm),
+            _ => None,
+        }
+    }
+
+    pub fn writer(&self) -> Box<dyn ImageWriter> {
+        match self {
+            ImageFormat::Png => Box::new(PngWriter),
+            ImageFormat::Ppm => Box::new(PpmWriter { binary: true }),
+            ImageFormat::PpmAscii => Box::new(PpmWriter { binary: false }),
+            ImageFormat::Exr => Box::new(ExrWriter { half: false }),
+            ImageFormat::ExrHalf => Box::new(ExrWriter { half: true }),
+            ImageFormat::Pfm => Box::new(PfmWriter),
+        }
+    }
+}
+
+pub fn save(fb: &Framebuffer, path: &Path, format: ImageFormat) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    format.writer().write(fb, &mut out)?;
+    out.flush()
+}
+
+/// Reads a PNG, PPM (P3 or P6), Radiance HDR or OpenEXR image. The 8-bit
+/// formats are decoded from sRGB to linear radiance; the others already are.
+pub fn load(path: &Path) -> io::Result<Framebuffer> {
+    let reader = BufReader::new(File::open(path)?);
+    let extension: Option<String> = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => read_png(reader),
+        Some("ppm") => read_ppm(reader),
+        Some("hdr") => read_hdr(reader),
+        Some("exr") => read_exr(reader),
+        _ => Err(invalid_data(
+            "unsupported image format; expected .png, .ppm, .hdr or .exr".to_string(),
+        )),
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+pub mod exr;
+pub mod hdr;
+pub mod pfm;
+pub mod png;
+pub mod ppm;
+
+pub use self::exr::{read_exr, ExrWriter};
+pub use self::png::{read_png, PngWriter};
+pub use hdr::read_hdr;
+pub use pfm::PfmWriter;
+pub use ppm::{read_ppm, PpmWriter};
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framebuffer_layout() {
+        let mut fb: Framebuffer = Framebuffer::new(3, 2);
+        fb.set(2, 1, Color::new(1.0, 0.0, 0.0));
+        assert_eq!(fb.pixels()[5], Color::new(1.0, 0.0, 0.0));
+        assert_eq!(fb.get(2, 1), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(&fb.to_srgb8()[15..], &[255, 0, 0]);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("out/render.PNG")),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("image.ppm")),
+            Some(ImageFormat::Ppm)
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("beauty.exr")),
+            Some(ImageFormat::Exr)
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("beauty.pfm")),
+            Some(ImageFormat::Pfm)
+        );
+        assert_eq!(ImageFormat::from_path(Path::new("image.tga")), None);
+        assert_eq!(ImageFormat::from_path(Path::new("image")), None);
+    }
+}
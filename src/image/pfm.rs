@@ -0,0 +1,51 @@
+use super::*;
+
+/// Portable Float Map writer: little-endian 32-bit float RGB, stored bottom
+/// row first as the format requires.
+pub struct PfmWriter;
+
+impl ImageWriter for PfmWriter {
+    fn write(&self, fb: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        // A negative scale marks the data as little-endian.
+        write!(out, "PF\n{} {}\n-1.0\n", fb.width(), fb.height())?;
+        let mut row: Vec<u8> = Vec::with_capacity(fb.width() * 12);
+        for y in (0..fb.height()).rev() {
+            row.clear();
+            for x in 0..fb.width() {
+                let c: Color = fb.get(x, y);
+                for channel in [c.x(), c.y(), c.z()] {
+                    row.extend_from_slice(&channel.to_le_bytes());
+                }
+            }
+            out.write_all(&row)?;
+        }
+        Ok(())
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let mut fb: Framebuffer = Framebuffer::new(1, 2);
+        fb.set(0, 0, Color::new(2.5, 0.0, 0.0));
+        fb.set(0, 1, Color::new(0.0, 0.0, 1.0));
+        let mut out: Vec<u8> = Vec::new();
+        PfmWriter.write(&fb, &mut out).unwrap();
+
+        let header: &[u8] = b"PF\n1 2\n-1.0\n";
+        assert_eq!(&out[..header.len()], header);
+        let floats: Vec<f32> = out[header.len()..]
+            .chunks(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        // The bottom row comes first.
+        assert_eq!(floats, vec![0.0, 0.0, 1.0, 2.5, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+/// 8-bit sRGB PNG writer.
+pub struct PngWriter;
+
+impl ImageWriter for PngWriter {
+    fn write(&self, fb: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        let mut encoder = ::png::Encoder::new(out, fb.width() as u32, fb.height() as u32);
+        encoder.set_color(::png::ColorType::Rgb);
+        encoder.set_depth(::png::BitDepth::Eight);
+        encoder.set_source_srgb(::png::SrgbRenderingIntent::Perceptual);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&fb.to_srgb8())?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+pub fn read_png<R: Read>(input: R) -> io::Result<Framebuffer> {
+    let mut decoder = ::png::Decoder::new(input);
+    // Palettes, low bit depths and 16-bit samples all come out as 8-bit channels.
+    decoder.set_transformations(::png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut data: Vec<u8> = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data)?;
+
+    let channels: usize = info.color_type.samples();
+    let pixels: Vec<Color> = data[..info.buffer_size()]
+        .chunks_exact(channels)
+        .map(|px| {
+            let s = |c: u8| c as f32 / color::COLOR_RANGE as f32;
+            match channels {
+                // Grayscale, with or without alpha.
+                1 | 2 => Color::from_srgb(s(px[0]), s(px[0]), s(px[0])),
+                _ => Color::from_srgb(s(px[0]), s(px[1]), s(px[2])),
+            }
+        })
+        .collect();
+    Ok(Framebuffer::from_pixels(
+        info.width as usize,
+        info.height as usize,
+        pixels,
+    ))
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut fb: Framebuffer = Framebuffer::new(2, 2);
+        fb.set(1, 0, Color::new(1.0, 0.5, 0.0));
+        let mut out: Vec<u8> = Vec::new();
+        PngWriter.write(&fb, &mut out).unwrap();
+
+        let decoder = ::png::Decoder::new(out.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut data: Vec<u8> = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!((info.width, info.height), (2, 2));
+        assert_eq!(&data[..info.buffer_size()], fb.to_srgb8().as_slice());
+
+        let read: Framebuffer = read_png(io::Cursor::new(out)).unwrap();
+        assert_eq!(read.to_srgb8(), fb.to_srgb8());
+    }
+}
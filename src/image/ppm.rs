@@ -0,0 +1,160 @@
+use super::*;
+
+/// Portable pixmap writer: binary (P6) or ASCII (P3), 8-bit sRGB.
+pub struct PpmWriter {
+    pub binary: bool,
+}
+
+impl ImageWriter for PpmWriter {
+    fn write(&self, fb: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        let magic: &str = if self.binary { "P6" } else { "P3" };
+        write!(
+            out,
+            "{}\n{} {}\n{}\n",
+            magic,
+            fb.width(),
+            fb.height(),
+            color::COLOR_RANGE
+        )?;
+
+        if self.binary {
+            return out.write_all(&fb.to_srgb8());
+        }
+        for c in fb.pixels() {
+            let [r, g, b] = c.to_srgb8();
+            writeln!(out, "{} {} {}", r, g, b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a P3 or P6 pixmap with any maximum value up to 65535.
+pub fn read_ppm<R: BufRead>(mut input: R) -> io::Result<Framebuffer> {
+    let magic: String = read_token(&mut input)?;
+    let binary: bool = match magic.as_str() {
+        "P6" => true,
+        "P3" => false,
+        _ => return Err(invalid_data(format!("not a P3 or P6 pixmap ('{}')", magic))),
+    };
+    let width: usize = read_number(&mut input)?;
+    let height: usize = read_number(&mut input)?;
+    let maxval: usize = read_number(&mut input)?;
+    if maxval == 0 || maxval > 65535 {
+        return Err(invalid_data(format!("invalid maximum value {}", maxval)));
+    }
+
+    let count: usize = width * height * 3;
+    let samples: Vec<usize> = if binary {
+        // A single whitespace byte, already consumed by `read_token`, precedes the data.
+        let bytes_per_sample: usize = if maxval < 256 { 1 } else { 2 };
+        let mut data: Vec<u8> = vec![0; count * bytes_per_sample];
+        input.read_exact(&mut data)?;
+        data.chunks_exact(bytes_per_sample)
+            .map(|b| b.iter().fold(0, |acc, &byte| acc << 8 | byte as usize))
+            .collect()
+    } else {
+        (0..count)
+            .map(|_| read_number(&mut input))
+            .collect::<io::Result<_>>()?
+    };
+
+    let pixels: Vec<Color> = samples
+        .chunks_exact(3)
+        .map(|s| {
+            let scale = |c: usize| c.min(maxval) as f32 / maxval as f32;
+            Color::from_srgb(scale(s[0]), scale(s[1]), scale(s[2]))
+        })
+        .collect();
+    Ok(Framebuffer::from_pixels(width, height, pixels))
+}
+
+/// Reads the next whitespace-separated header token, skipping `#` comments, and
+/// consumes the single whitespace byte that ends it.
+fn read_token<R: BufRead>(input: &mut R) -> io::Result<String> {
+    let mut token: String = String::new();
+    let mut byte: [u8; 1] = [0];
+    loop {
+        if input.read(&mut byte)? == 0 {
+            if token.is_empty() {
+                return Err(invalid_data("unexpected end of pixmap".to_string()));
+            }
+            return Ok(token);
+        }
+        match byte[0] {
+            b'#' if token.is_empty() => {
+                let mut comment: Vec<u8> = Vec::new();
+                input.read_until(b'\n', &mut comment)?;
+            }
+            c if c.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            c => token.push(c as char),
+        }
+    }
+}
+
+fn read_number<R: BufRead>(input: &mut R) -> io::Result<usize> {
+    let token: String = read_token(input)?;
+    token
+        .parse::<usize>()
+        .map_err(|_| invalid_data(format!("expected a number, found '{}'", token)))
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_pixels() -> Framebuffer {
+        let mut fb: Framebuffer = Framebuffer::new(2, 1);
+        fb.set(0, 0, Color::new(1.0, 0.0, 0.0));
+        fb.set(1, 0, Color::new(0.0, 0.0, 1.0));
+        fb
+    }
+
+    #[test]
+    fn test_binary() {
+        let mut out: Vec<u8> = Vec::new();
+        PpmWriter { binary: true }
+            .write(&two_pixels(), &mut out)
+            .unwrap();
+        assert_eq!(out, b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff".to_vec());
+    }
+
+    #[test]
+    fn test_ascii() {
+        let mut out: Vec<u8> = Vec::new();
+        PpmWriter { binary: false }
+            .write(&two_pixels(), &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "P3\n2 1\n255\n255 0 0\n0 0 255\n"
+        );
+    }
+
+    #[test]
+    fn test_read_round_trip() {
+        for binary in [true, false] {
+            let mut out: Vec<u8> = Vec::new();
+            PpmWriter { binary }.write(&two_pixels(), &mut out).unwrap();
+            let fb: Framebuffer = read_ppm(out.as_slice()).unwrap();
+            assert_eq!(fb.to_srgb8(), two_pixels().to_srgb8());
+        }
+    }
+
+    #[test]
+    fn test_read_comments_and_16_bit() {
+        let source: &[u8] = b"P6 # comment\n# another\n1 1\n65535\n\xff\xff\x00\x00\x80\x00";
+        let fb: Framebuffer = read_ppm(source).unwrap();
+        assert_eq!(fb.to_srgb8(), vec![255, 0, 128]);
+
+        let err: io::Error = read_ppm(&b"P5\n1 1\n255\n\x00"[..]).err().unwrap();
+        assert_eq!(err.to_string(), "not a P3 or P6 pixmap ('P5')");
+    }
+}
@@ -0,0 +1,14 @@
+pub mod camera;
+pub mod environment;
+pub mod image;
+pub mod loader;
+pub mod material;
+pub mod object;
+pub mod pdf;
+pub mod ray;
+pub mod render;
+pub mod rtweekend;
+pub mod sampler;
+pub mod scene;
+pub mod texture;
+pub mod vec3;
@@ -0,0 +1,76 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Error raised while reading an asset, pointing at the file and, when the
+/// problem is in its contents, the 1-based line number.
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl LoadError {
+    pub fn new(path: &Path, line: Option<usize>, message: String) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn read_to_string(path: &Path) -> Result<String, LoadError> {
+    std::fs::read_to_string(path).map_err(|e| LoadError::new(path, None, e.to_string()))
+}
+
+/// Parses exactly `N` floats from `args`, naming `keyword` in the error.
+fn parse_floats<const N: usize>(keyword: &str, args: &[&str]) -> Result<[f32; N], String> {
+    parse_optional_floats::<N>(keyword, args, N)
+}
+
+/// Parses up to `N` floats from `args`, of which the first `required` must be
+/// present; the missing ones are zero.
+fn parse_optional_floats<const N: usize>(
+    keyword: &str,
+    args: &[&str],
+    required: usize,
+) -> Result<[f32; N], String> {
+    if args.len() < required {
+        let expected: String = if required == N {
+            N.to_string()
+        } else {
+            format!("{} to {}", required, N)
+        };
+        return Err(format!(
+            "'{}' expects {} numbers, found {}",
+            keyword,
+            expected,
+            args.len()
+        ));
+    }
+    let mut out: [f32; N] = [0.0; N];
+    for (o, a) in out.iter_mut().zip(args.iter()) {
+        *o = a
+            .parse::<f32>()
+            .map_err(|_| format!("'{}' has invalid number '{}'", keyword, a))?;
+    }
+    Ok(out)
+}
+
+pub mod mtl;
+pub mod obj;
+
+pub use mtl::load_mtl;
+pub use obj::load_obj;
@@ -0,0 +1,203 @@
+use super::*;
+use crate::material::*;
+use crate::texture::*;
+use crate::vec3::*;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The subset of an MTL `newmtl` block that maps onto our materials.
+#[derive(Clone)]
+struct MtlEntry {
+    kd: Color,
+    map_kd: Option<Arc<dyn Texture>>,
+    ks: Color,
+    ke: Color,
+    ns: f32,
+    ni: f32,
+    d: f32,
+    illum: u32,
+}
+
+impl Default for MtlEntry {
+    fn default() -> Self {
+        Self {
+            kd: Color::new(0.8, 0.8, 0.8),
+            map_kd: None,
+            ks: Color::new(0.0, 0.0, 0.0),
+            ke: Color::new(0.0, 0.0, 0.0),
+            ns: 0.0,
+            ni: 1.5,
+            d: 1.0,
+            illum: 2,
+        }
+    }
+}
+
+impl MtlEntry {
+    /// Any emission makes a light. Otherwise illumination models 4, 6, 7 and 9
+    /// (or any transparency) are glass, 3 and 5 are mirrors, and everything else
+    /// is treated as a plain diffuse surface.
+    fn to_material(&self) -> Arc<dyn Material> {
+        if self.ke.length_squared() > 0.0 {
+            return Arc::new(DiffuseLight::new(self.ke));
+        }
+        match self.illum {
+            4 | 6 | 7 | 9 => Arc::new(Dielectric::new(self.ni)),
+            _ if self.d < 1.0 => Arc::new(Dielectric::new(self.ni)),
+            3 | 5 => {
+                // Map the Phong exponent onto fuzz: a sharp highlight (large Ns)
+                // is a clean mirror, a broad one (small Ns) a rough metal.
+                let fuzz: f32 = (2.0 / (self.ns + 2.0)).sqrt();
+                Arc::new(Metal::new(self.ks, fuzz))
+            }
+            _ => match &self.map_kd {
+                Some(texture) => Arc::new(Lambertian::from_texture(texture.clone())),
+                None => Arc::new(Lambertian::new(self.kd)),
+            },
+        }
+    }
+}
+
+pub fn load_mtl(path: &Path) -> Result<HashMap<String, Arc<dyn Material>>, LoadError> {
+    parse_mtl(&read_to_string(path)?, path)
+}
+
+pub fn parse_mtl(
+    source: &str,
+    path: &Path,
+) -> Result<HashMap<String, Arc<dyn Material>>, LoadError> {
+    let mut entries: Vec<(String, MtlEntry)> = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let error = |message: String| LoadError::new(path, Some(index + 1), message);
+        let line: &str = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let keyword: &str = match tokens.next() {
+            Some(k) => k,
+            None => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(error("'newmtl' needs a name".to_string()));
+            }
+            entries.push((args.join(" "), MtlEntry::default()));
+            continue;
+        }
+
+        let entry: &mut MtlEntry = match entries.last_mut() {
+            Some((_, entry)) => entry,
+            None => return Err(error(format!("'{}' before any 'newmtl'", keyword))),
+        };
+        match keyword {
+            "Kd" => {
+                let [r, g, b] = parse_floats::<3>(keyword, &args).map_err(error)?;
+                entry.kd = Color::new(r, g, b);
+            }
+            "Ks" => {
+                let [r, g, b] = parse_floats::<3>(keyword, &args).map_err(error)?;
+                entry.ks = Color::new(r, g, b);
+            }
+            "Ke" => {
+                let [r, g, b] = parse_floats::<3>(keyword, &args).map_err(error)?;
+                entry.ke = Color::new(r, g, b);
+            }
+            "Ns" => entry.ns = parse_floats::<1>(keyword, &args).map_err(error)?[0],
+            "Ni" => entry.ni = parse_floats::<1>(keyword, &args).map_err(error)?[0],
+            "d" => entry.d = parse_floats::<1>(keyword, &args).map_err(error)?[0],
+            "Tr" => entry.d = 1.0 - parse_floats::<1>(keyword, &args).map_err(error)?[0],
+            "illum" => {
+                entry.illum = args
+                    .first()
+                    .and_then(|a| a.parse::<u32>().ok())
+                    .ok_or_else(|| error("'illum' expects an integer".to_string()))?;
+            }
+            "map_Kd" => {
+                // Texture options come before the file name, which is last.
+                let file: &str = args
+                    .last()
+                    .ok_or_else(|| error("'map_Kd' needs a file name".to_string()))?;
+                let dir: &Path = path.parent().unwrap_or_else(|| Path::new(""));
+                let texture: ImageTexture =
+                    ImageTexture::load(&dir.join(file), WrapMode::Repeat)
+                        .map_err(|e| error(format!("{}: {}", dir.join(file).display(), e)))?;
+                entry.map_kd = Some(Arc::new(texture));
+            }
+            // Ambient statements and the other texture maps have no equivalent yet.
+            _ => {}
+        }
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|(name, entry)| (name, entry.to_material()))
+        .collect())
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let source = "\
+# exported
+newmtl red
+Kd 0.8 0.1 0.1
+illum 2
+
+newmtl glass
+Ni 1.33
+illum 7
+
+newmtl chrome
+Ks 0.9 0.9 0.9
+Ns 500
+illum 3
+";
+        let materials = parse_mtl(source, Path::new("test.mtl")).unwrap();
+        assert_eq!(materials.len(), 3);
+        assert!(materials.contains_key("red"));
+        assert!(materials.contains_key("glass"));
+        assert!(materials.contains_key("chrome"));
+    }
+
+    #[test]
+    fn test_to_material_selection() {
+        let glass: MtlEntry = MtlEntry {
+            d: 0.5,
+            ..MtlEntry::default()
+        };
+        let material: Arc<dyn Material> = glass.to_material();
+        let r = crate::ray::Ray::new(Point3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec =
+            crate::object::HitRecord::new(&r, 1.0, &Vec3::new(0.0, 0.0, 1.0), material.as_ref());
+        // Glass never absorbs, so attenuation is exactly white.
+        let srec = rec
+            .mat_ptr
+            .scatter(&r, &rec, &mut crate::rtweekend::Rng::new(0))
+            .unwrap();
+        assert_eq!(srec.attenuation, Color::new(1.0, 1.0, 1.0));
+        assert!(matches!(srec.kind, ScatterKind::Specular(_)));
+    }
+
+    #[test]
+    fn test_error_reports_line() {
+        let source = "newmtl a\nKd 1 0.5\n";
+        let err: LoadError = parse_mtl(source, Path::new("bad.mtl")).err().unwrap();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(
+            err.to_string(),
+            "bad.mtl:2: 'Kd' expects 3 numbers, found 2"
+        );
+
+        let err: LoadError = parse_mtl("Kd 1 1 1\n", Path::new("bad.mtl")).err().unwrap();
+        assert_eq!(err.to_string(), "bad.mtl:1: 'Kd' before any 'newmtl'");
+    }
+}
@@ -0,0 +1,336 @@
+use super::*;
+use crate::material::*;
+use crate::object::*;
+use crate::vec3::*;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A corner of a face: indices into the file's `v`, `vt` and `vn` lists.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Triangles sharing one material. OBJ indexes positions, uvs and normals
+/// separately, so every distinct corner becomes one mesh vertex.
+struct Group {
+    mat_ptr: Arc<dyn Material>,
+    vertices: Vec<Corner>,
+    lookup: HashMap<Corner, usize>,
+    indices: Vec<[usize; 3]>,
+}
+
+impl Group {
+    fn new(mat_ptr: Arc<dyn Material>) -> Self {
+        Self {
+            mat_ptr,
+            vertices: Vec::new(),
+            lookup: HashMap::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    fn vertex(&mut self, corner: Corner) -> usize {
+        let vertices: &mut Vec<Corner> = &mut self.vertices;
+        *self.lookup.entry(corner).or_insert_with(|| {
+            vertices.push(corner);
+            vertices.len() - 1
+        })
+    }
+
+    /// Vertex normals and uvs are only kept when every corner has one.
+    fn into_mesh(self, positions: &[Point3], uvs: &[(f32, f32)], normals: &[Vec3]) -> TriangleMesh {
+        let mesh_positions: Vec<Point3> = self.vertices.iter().map(|c| positions[c.0]).collect();
+        let mesh_uvs: Option<Vec<(f32, f32)>> =
+            self.vertices.iter().map(|c| c.1.map(|i| uvs[i])).collect();
+        let mesh_normals: Option<Vec<Vec3>> = self
+            .vertices
+            .iter()
+            .map(|c| c.2.map(|i| normals[i]))
+            .collect();
+
+        let mut mesh: TriangleMesh = TriangleMesh::new(mesh_positions, self.indices, self.mat_ptr);
+        if let Some(uvs) = mesh_uvs {
+            mesh = mesh.with_uvs(uvs);
+        }
+        if let Some(normals) = mesh_normals {
+            mesh = mesh.with_normals(normals);
+        }
+        mesh
+    }
+}
+
+/// Loads a Wavefront OBJ file as one triangle mesh per material. Faces before
+/// any `usemtl`, or naming a material no `mtllib` defines, use `default_material`.
+pub fn load_obj(
+    path: &Path,
+    default_material: Arc<dyn Material>,
+) -> Result<Vec<TriangleMesh>, LoadError> {
+    parse_obj(&read_to_string(path)?, path, default_material)
+}
+
+pub fn parse_obj(
+    source: &str,
+    path: &Path,
+    default_material: Arc<dyn Material>,
+) -> Result<Vec<TriangleMesh>, LoadError> {
+    let mut positions: Vec<Point3> = Vec::new();
+    let mut uvs: Vec<(f32, f32)> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
+
+    let mut materials: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    let mut groups: Vec<Group> = Vec::new();
+    let mut group_names: HashMap<String, usize> = HashMap::new();
+    let mut current: Option<usize> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let error = |message: String| LoadError::new(path, Some(index + 1), message);
+        let line: &str = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let keyword: &str = match tokens.next() {
+            Some(k) => k,
+            None => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                let [x, y, z] = parse_floats::<3>(keyword, &args).map_err(error)?;
+                positions.push(Point3::new(x, y, z));
+            }
+            "vt" => {
+                // v defaults to 0 for one-dimensional textures; w is unused.
+                let [u, v, _] = parse_optional_floats::<3>(keyword, &args, 1).map_err(error)?;
+                uvs.push((u, v));
+            }
+            "vn" => {
+                let [x, y, z] = parse_floats::<3>(keyword, &args).map_err(error)?;
+                let normal: Vec3 = Vec3::new(x, y, z);
+                if normal.length_squared() == 0.0 {
+                    return Err(error("'vn' must not be zero".to_string()));
+                }
+                normals.push(normal.unit_vector());
+            }
+            "mtllib" => {
+                let dir: &Path = path.parent().unwrap_or_else(|| Path::new(""));
+                for library in args.iter() {
+                    materials.extend(load_mtl(&dir.join(library))?);
+                }
+            }
+            "usemtl" => {
+                let name: String = args.join(" ");
+                let next: usize = groups.len();
+                let group: usize = *group_names.entry(name.clone()).or_insert(next);
+                if group == next {
+                    let mat_ptr: Arc<dyn Material> = materials
+                        .get(&name)
+                        .cloned()
+                        .unwrap_or_else(|| default_material.clone());
+                    groups.push(Group::new(mat_ptr));
+                }
+                current = Some(group);
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error(format!(
+                        "'f' needs at least 3 vertices, found {}",
+                        args.len()
+                    )));
+                }
+                let corners: Vec<Corner> = args
+                    .iter()
+                    .map(|a| parse_corner(a, positions.len(), uvs.len(), normals.len()))
+                    .collect::<Result<_, _>>()
+                    .map_err(error)?;
+
+                let group: usize = match current {
+                    Some(g) => g,
+                    None => {
+                        groups.push(Group::new(default_material.clone()));
+                        current = Some(groups.len() - 1);
+                        groups.len() - 1
+                    }
+                };
+                let group: &mut Group = &mut groups[group];
+                // Polygons are split into a fan around their first corner.
+                let first: usize = group.vertex(corners[0]);
+                for pair in corners[1..].windows(2) {
+                    let second: usize = group.vertex(pair[0]);
+                    let third: usize = group.vertex(pair[1]);
+                    group.indices.push([first, second, third]);
+                }
+            }
+            // Object/group names, smoothing groups and lines carry nothing we render.
+            _ => {}
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|g| !g.indices.is_empty())
+        .map(|g| g.into_mesh(&positions, &uvs, &normals))
+        .collect())
+}
+
+/// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`, resolving 1-based and negative
+/// (relative to the end) indices against the elements read so far.
+fn parse_corner(
+    token: &str,
+    positions: usize,
+    uvs: usize,
+    normals: usize,
+) -> Result<Corner, String> {
+    let mut parts = token.split('/');
+    let resolve = |part: Option<&str>, count: usize, what: &str| -> Result<Option<usize>, String> {
+        let part: &str = match part {
+            Some(p) if !p.is_empty() => p,
+            _ => return Ok(None),
+        };
+        let i: i64 = part
+            .parse::<i64>()
+            .map_err(|_| format!("invalid {} index '{}'", what, part))?;
+        let resolved: i64 = if i < 0 { count as i64 + i } else { i - 1 };
+        if i == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(format!(
+                "{} index {} out of range ({} defined)",
+                what, i, count
+            ));
+        }
+        Ok(Some(resolved as usize))
+    };
+
+    let position: Option<usize> = resolve(parts.next(), positions, "vertex")?;
+    let uv: Option<usize> = resolve(parts.next(), uvs, "texture coordinate")?;
+    let normal: Option<usize> = resolve(parts.next(), normals, "normal")?;
+    match position {
+        Some(p) => Ok((p, uv, normal)),
+        None => Err(format!("face corner '{}' has no vertex index", token)),
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn test_quad_shares_vertices() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1 4/4/1
+";
+        let meshes: Vec<TriangleMesh> =
+            parse_obj(source, Path::new("quad.obj"), default_material()).unwrap();
+        assert_eq!(meshes.len(), 1);
+        let mesh: &TriangleMesh = &meshes[0];
+        assert_eq!(mesh.len(), 2);
+        assert_eq!(mesh.face(0), [0, 1, 2]);
+        assert_eq!(mesh.face(1), [0, 2, 3]);
+        assert_eq!(mesh.uv(2), Some((1.0, 1.0)));
+        assert_eq!(mesh.normal(3), Some(Vec3::new(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn test_negative_indices_and_missing_attributes() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f -3 -2 -1
+";
+        let meshes: Vec<TriangleMesh> =
+            parse_obj(source, Path::new("tri.obj"), default_material()).unwrap();
+        assert_eq!(meshes[0].face(0), [0, 1, 2]);
+        assert_eq!(meshes[0].position(2), Point3::new(0.0, 1.0, 0.0));
+        assert_eq!(meshes[0].uv(0), None);
+        assert_eq!(meshes[0].normal(0), None);
+    }
+
+    #[test]
+    fn test_texture_coordinates_take_one_to_three_numbers() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0.25
+vt 0.5 0.75
+vt 1 0.5 0
+f 1/1 2/2 3/3
+";
+        let meshes: Vec<TriangleMesh> =
+            parse_obj(source, Path::new("uv.obj"), default_material()).unwrap();
+        assert_eq!(meshes[0].uv(0), Some((0.25, 0.0)));
+        assert_eq!(meshes[0].uv(1), Some((0.5, 0.75)));
+        assert_eq!(meshes[0].uv(2), Some((1.0, 0.5)));
+
+        let err: LoadError = parse_obj("vt\n", Path::new("bad.obj"), default_material())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "bad.obj:1: 'vt' expects 1 to 3 numbers, found 0"
+        );
+    }
+
+    #[test]
+    fn test_groups_by_material() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+usemtl a
+f 1 2 3
+usemtl b
+f 1 3 2
+usemtl a
+f 3 2 1
+";
+        let meshes: Vec<TriangleMesh> =
+            parse_obj(source, Path::new("groups.obj"), default_material()).unwrap();
+        assert_eq!(meshes.len(), 2);
+        assert_eq!(meshes[0].len(), 2);
+        assert_eq!(meshes[1].len(), 1);
+    }
+
+    #[test]
+    fn test_errors_report_line() {
+        let source = "v 0 0 0\nv 1 0 0\nf 1 2 3\n";
+        let err: LoadError = parse_obj(source, Path::new("bad.obj"), default_material())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "bad.obj:3: vertex index 3 out of range (2 defined)"
+        );
+
+        let source = "v 0 0\n";
+        let err: LoadError = parse_obj(source, Path::new("bad.obj"), default_material())
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "bad.obj:1: 'v' expects 3 numbers, found 2");
+
+        let source = "v 0 0 0\nvn 0 0 0\n";
+        let err: LoadError = parse_obj(source, Path::new("bad.obj"), default_material())
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "bad.obj:2: 'vn' must not be zero");
+
+        let err: LoadError = load_obj(Path::new("does/not/exist.obj"), default_material())
+            .err()
+            .unwrap();
+        assert_eq!(err.line, None);
+    }
+}
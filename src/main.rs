@@ -1,142 +1,277 @@
-mod camera;
-mod material;
-mod object;
-mod ray;
-mod rtweekend;
-mod vec3;
-
-use camera::*;
-use material::*;
-use object::*;
-use rtweekend::*;
-use vec3::*;
-
-use std::fs::File;
-use std::io::prelude::*;
-
-use std::cell::RefCell;
-use std::rc::Rc;
-
-use indicatif::ProgressBar;
-
-const ASPECT_RATIO: f32 = 16.0 / 9.0;
-const IMAGE_WIDTH: u16 = 384;
-const IMAGE_HEIGHT: u16 = (IMAGE_WIDTH as f32 / ASPECT_RATIO) as u16;
-const SAMPLES_PER_PIXEL: u16 = 100;
-const DEPTH: u16 = 10;
-
-fn main() -> std::io::Result<()> {
-    create_image()
+use ray_tracing::camera::*;
+use ray_tracing::image::*;
+use ray_tracing::object::*;
+use ray_tracing::ray::MisHeuristic;
+use ray_tracing::render::*;
+use ray_tracing::rtweekend::*;
+use ray_tracing::sampler::SamplerKind;
+use ray_tracing::scene::*;
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    /// 8-bit sRGB PNG
+    Png,
+    /// Binary portable pixmap (P6)
+    Ppm,
+    /// ASCII portable pixmap (P3)
+    PpmAscii,
+    /// OpenEXR, 32-bit float RGB
+    Exr,
+    /// OpenEXR, 16-bit half float RGB
+    ExrHalf,
+    /// Portable float map
+    Pfm,
 }
 
-fn random_scene() -> HittableList {
-    let mut world: HittableList = HittableList::new();
-    let ground_material: Rc<RefCell<dyn Material>> =
-        Rc::new(RefCell::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))));
-
-    world.add(Rc::new(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material,
-    )));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let choose_mat: f32 = random_double();
-            let center: Point3 = Point3::new(
-                a as f32 + 0.9 * random_double(),
-                0.2,
-                b as f32 + 0.9 * random_double(),
-            );
+impl From<Format> for ImageFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Png => ImageFormat::Png,
+            Format::Ppm => ImageFormat::Ppm,
+            Format::PpmAscii => ImageFormat::PpmAscii,
+            Format::Exr => ImageFormat::Exr,
+            Format::ExrHalf => ImageFormat::ExrHalf,
+            Format::Pfm => ImageFormat::Pfm,
+        }
+    }
+}
 
-            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
-                world.add(Rc::new(Sphere::new(
-                    center,
-                    0.2,
-                    if choose_mat < 0.8 {
-                        Rc::new(RefCell::new(Lambertian::new(
-                            Color::random() * Color::random(),
-                        )))
-                    } else if choose_mat < 0.95 {
-                        Rc::new(RefCell::new(Metal::new(
-                            Color::random_with_range(0.5, 1.0),
-                            random_double_with_range(0.0, 0.5),
-                        )))
-                    } else {
-                        Rc::new(RefCell::new(Dielectric::new(1.5)))
-                    },
-                )));
-            }
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Mis {
+    /// Weight each sample by its share of the densities
+    Balance,
+    /// Weight by squared densities, favouring the better strategy more
+    Power,
+}
+
+impl From<Mis> for MisHeuristic {
+    fn from(mis: Mis) -> Self {
+        match mis {
+            Mis::Balance => MisHeuristic::Balance,
+            Mis::Power => MisHeuristic::Power,
         }
     }
+}
 
-    let material1: Rc<RefCell<dyn Material>> = Rc::new(RefCell::new(Dielectric::new(1.5)));
-    world.add(Rc::new(Sphere::new(
-        Point3::new(0.0, 1.0, 0.0),
-        1.0,
-        material1,
-    )));
-
-    let material2: Rc<RefCell<dyn Material>> =
-        Rc::new(RefCell::new(Lambertian::new(Color::new(0.4, 0.2, 0.1))));
-    world.add(Rc::new(Sphere::new(
-        Point3::new(-4.0, 1.0, 0.0),
-        1.0,
-        material2,
-    )));
-
-    let material3: Rc<RefCell<dyn Material>> =
-        Rc::new(RefCell::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0)));
-    world.add(Rc::new(Sphere::new(
-        Point3::new(4.0, 1.0, 0.0),
-        1.0,
-        material3,
-    )));
-
-    return world;
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum SamplerArg {
+    /// Independent uniform random numbers
+    Independent,
+    /// Jittered strata, one per sample in each dimension
+    Stratified,
+    /// The Halton sequence, randomly shifted in each pixel
+    Halton,
+    /// The Owen-scrambled Sobol sequence; best with power-of-two sample counts
+    Sobol,
 }
 
-fn create_image() -> std::io::Result<()> {
-    let mut file = File::create("image.ppm")?;
-
-    file.write_fmt(format_args!(
-        "P3\n{} {}\n{}\n",
-        IMAGE_WIDTH,
-        IMAGE_HEIGHT,
-        color::COLOR_RANGE
-    ))?;
-
-    let world: HittableList = random_scene();
-
-    let lookfrom: Point3 = Point3::new(13.0, 2.0, 3.0);
-    let lookat: Point3 = Point3::new(0.0, 0.0, 0.0);
-    let vup: Vec3 = Vec3::new(0.0, 1.0, 0.0);
-
-    let aperture: f32 = 0.1;
-    let dist_to_focus: f32 = 10.0;
-
-    let cam: Camera = Camera::new(
-        lookfrom,
-        lookat,
-        vup,
-        20.0,
-        ASPECT_RATIO,
-        aperture,
-        dist_to_focus,
-    );
-    let pb: ProgressBar = ProgressBar::new(IMAGE_HEIGHT as u64);
-    for j in (0..IMAGE_HEIGHT).rev() {
-        pb.inc(1);
-        for i in 0..IMAGE_WIDTH {
-            let color = (0..SAMPLES_PER_PIXEL).fold(Color::new(0.0, 0.0, 0.0), |acc, _| {
-                let u = (i as f32 + random_double()) / (IMAGE_WIDTH - 1) as f32;
-                let v = (j as f32 + random_double()) / (IMAGE_HEIGHT - 1) as f32;
-                acc + cam.get_ray(u, v).ray_color(&world, DEPTH)
-            });
-            color.write_to_file(&mut file, SAMPLES_PER_PIXEL)?;
+impl From<SamplerArg> for SamplerKind {
+    fn from(sampler: SamplerArg) -> Self {
+        match sampler {
+            SamplerArg::Independent => SamplerKind::Independent,
+            SamplerArg::Stratified => SamplerKind::Stratified,
+            SamplerArg::Halton => SamplerKind::Halton,
+            SamplerArg::Sobol => SamplerKind::Sobol,
         }
     }
-    pb.finish_with_message("Done");
+}
+
+/// Renders a scene file, or one of the built-in scenes, to an image.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// JSON scene description to render
+    #[arg(conflicts_with = "builtin")]
+    scene: Option<PathBuf>,
+
+    /// Built-in scene to render when no scene file is given
+    #[arg(long, default_value = "random", value_parser = clap::builder::PossibleValuesParser::new(BUILTIN_SCENES))]
+    builtin: String,
+
+    /// Image width in pixels; keeps the scene's aspect ratio if no height is given
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    width: Option<usize>,
+
+    /// Image height in pixels; keeps the scene's aspect ratio if no width is given
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    height: Option<usize>,
+
+    /// Samples per pixel
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    spp: Option<u16>,
+
+    /// Maximum number of bounces per path
+    #[arg(long)]
+    max_depth: Option<u16>,
+
+    /// Bounces before Russian roulette may end a path
+    #[arg(long)]
+    min_bounces: Option<u16>,
+
+    /// Multiple importance sampling heuristic for combining light and material
+    /// samples
+    #[arg(long, value_enum)]
+    mis: Option<Mis>,
+
+    /// Keep sampling a pixel until its relative error falls below this
+    #[arg(long)]
+    adaptive_threshold: Option<f32>,
+
+    /// Most samples an adaptive render spends on one pixel [default: 8 × spp]
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    max_spp: Option<u16>,
+
+    /// How the random numbers of each pixel's samples are spread out
+    #[arg(long, value_enum)]
+    sampler: Option<SamplerArg>,
+
+    /// Output image path; the format follows its extension (.png, .ppm, .exr or .pfm)
+    #[arg(short, long, default_value = "image.ppm")]
+    output: PathBuf,
+
+    /// Output image format, overriding the one implied by the extension
+    #[arg(long, value_enum)]
+    format: Option<Format>,
 
+    /// Also write a greyscale map of the samples taken in each pixel, relative
+    /// to the most allowed
+    #[arg(long)]
+    sample_map: Option<PathBuf>,
+
+    /// Seed for scene generation and sampling; renders with the same seed match
+    /// exactly, whatever the thread count [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Number of render threads [default: all cores]
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: Option<u16>,
+}
+
+impl Cli {
+    /// Applies the command-line overrides on top of the scene's own settings.
+    fn settings(&self, scene: &RenderSettings) -> RenderSettings {
+        let (width, height) = match (self.width, self.height) {
+            (Some(w), Some(h)) => (w, h),
+            (Some(w), None) => (w, ((w as f32 / scene.aspect_ratio()) as usize).max(1)),
+            (None, Some(h)) => (((h as f32 * scene.aspect_ratio()) as usize).max(1), h),
+            (None, None) => (scene.width, scene.height),
+        };
+        let samples_per_pixel: u16 = self.spp.unwrap_or(scene.samples_per_pixel);
+        let adaptive: Option<AdaptiveSettings> = match (self.adaptive_threshold, scene.adaptive) {
+            (Some(threshold), Some(adaptive)) => Some(AdaptiveSettings {
+                threshold,
+                ..adaptive
+            }),
+            (Some(threshold), None) => Some(AdaptiveSettings::new(threshold, samples_per_pixel)),
+            (None, adaptive) => adaptive,
+        }
+        .map(|adaptive| AdaptiveSettings {
+            max_samples: self.max_spp.unwrap_or(adaptive.max_samples),
+            ..adaptive
+        });
+        RenderSettings {
+            width,
+            height,
+            samples_per_pixel,
+            adaptive,
+            max_depth: self.max_depth.unwrap_or(scene.max_depth),
+            min_bounces: self.min_bounces.unwrap_or(scene.min_bounces),
+            mis: self.mis.map_or(scene.mis, |mis| mis.into()),
+            sampler: self.sampler.map_or(scene.sampler, |sampler| sampler.into()),
+        }
+    }
+}
+
+fn main() {
+    let cli: Cli = Cli::parse();
+    let format: ImageFormat = match cli.format {
+        Some(format) => format.into(),
+        None => format_of(&cli.output, ", or pass --format"),
+    };
+    if let Some(threshold) = cli.adaptive_threshold {
+        if !threshold.is_finite() || threshold <= 0.0 {
+            eprintln!("error: --adaptive-threshold must be a positive number");
+            std::process::exit(1);
+        }
+    }
+    let sample_map: Option<(&Path, ImageFormat)> = cli
+        .sample_map
+        .as_deref()
+        .map(|path| (path, format_of(path, "")));
+    let seed: u64 = cli.seed.unwrap_or_else(Rng::random_seed);
+
+    let scene: Scene = match &cli.scene {
+        Some(path) => match load_scene(path) {
+            Ok(scene) => scene,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => builtin_scene(&cli.builtin, &mut Rng::new(seed))
+            .expect("clap only accepts known scene names"),
+    };
+
+    if let Err((path, e)) = create_image(&cli, scene, format, sample_map, seed) {
+        eprintln!("error: {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
+}
+
+/// The format implied by `path`'s extension, or exits with `hint` appended to
+/// the error.
+fn format_of(path: &Path, hint: &str) -> ImageFormat {
+    match ImageFormat::from_path(path) {
+        Some(format) => format,
+        None => {
+            eprintln!(
+                "error: {}: unknown image extension; use .png, .ppm, .exr or .pfm{}",
+                path.display(),
+                hint
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn create_image<'a>(
+    cli: &'a Cli,
+    scene: Scene,
+    format: ImageFormat,
+    sample_map: Option<(&'a Path, ImageFormat)>,
+    seed: u64,
+) -> Result<(), (&'a Path, std::io::Error)> {
+    let settings: RenderSettings = cli.settings(&scene.settings);
+    let cam: Camera = scene.camera.camera(settings.aspect_ratio());
+    let world: HittableList = build_bvh(scene.world);
+
+    let renderer = Renderer {
+        world: &world,
+        lights: &scene.lights,
+        cam: &cam,
+        environment: scene.environment.as_ref(),
+        path: settings.path(),
+        width: settings.width,
+        height: settings.height,
+        samples_per_pixel: settings.samples_per_pixel,
+        adaptive: settings.adaptive,
+        sampler: settings.sampler,
+        threads: cli.threads.map_or_else(
+            || std::thread::available_parallelism().map_or(1, |n| n.get()),
+            |n| n as usize,
+        ),
+        seed,
+    };
+    let rendered: Rendered = renderer.render();
+    save(&rendered.image, &cli.output, format).map_err(|e| (cli.output.as_path(), e))?;
+    if let Some((path, format)) = sample_map {
+        let map: Framebuffer = rendered.sample_map(renderer.max_samples());
+        save(&map, path, format).map_err(|e| (path, e))?;
+    }
     Ok(())
 }
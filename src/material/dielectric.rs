@@ -11,20 +11,13 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(
-        &mut self,
-        r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, rng: &mut Rng) -> Option<ScatterRecord> {
         let etai_over_etat: f32 = if rec.front_face {
             1.0 / self.ref_idx
         } else {
             self.ref_idx
         };
         let unit_direction: Vec3 = r_in.direction().unit_vector();
-        *attenuation = Color::new(1.0, 1.0, 1.0);
 
         let mut cos_theta: f32 = (-unit_direction).dot(&rec.normal);
         if cos_theta > 1.0 {
@@ -32,14 +25,16 @@ impl Material for Dielectric {
         }
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
         let reflect_prob: f32 = schlick(cos_theta, etai_over_etat);
-        if etai_over_etat * sin_theta > 1.0 || random_double() < reflect_prob {
-            let reflected: Vec3 = Vec3::reflect(&unit_direction, &rec.normal);
-            *scattered = Ray::new(rec.p, reflected);
-            return true;
-        }
+        let direction: Vec3 =
+            if etai_over_etat * sin_theta > 1.0 || rng.random_double() < reflect_prob {
+                Vec3::reflect(&unit_direction, &rec.normal)
+            } else {
+                Vec3::refract(&unit_direction, &rec.normal, etai_over_etat)
+            };
 
-        let refracted: Vec3 = Vec3::refract(&unit_direction, &rec.normal, etai_over_etat);
-        *scattered = Ray::new(rec.p, refracted);
-        true
+        Some(ScatterRecord {
+            attenuation: Color::new(1.0, 1.0, 1.0),
+            kind: ScatterKind::Specular(Ray::with_time(rec.p, direction, r_in.time())),
+        })
     }
 }
@@ -0,0 +1,43 @@
+use super::*;
+
+/// The Henyey–Greenstein phase function of a participating medium; see
+/// `HenyeyGreensteinPdf` for the meaning of the asymmetry `g`.
+pub struct HenyeyGreenstein {
+    albedo: Arc<dyn Texture>,
+    g: f32,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(a: Color, g: f32) -> Self {
+        HenyeyGreenstein::from_texture(Arc::new(SolidColor::new(a)), g)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, g: f32) -> Self {
+        assert!(
+            g > -1.0 && g < 1.0,
+            "Henyey-Greenstein asymmetry must lie in (-1, 1)."
+        );
+        Self { albedo, g }
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, _rng: &mut Rng) -> Option<ScatterRecord> {
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(rec.u, rec.v, &rec.p),
+            kind: ScatterKind::Diffuse(Box::new(HenyeyGreensteinPdf::new(
+                &r_in.direction(),
+                self.g,
+            ))),
+        })
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, _rec: &HitRecord, scattered: &Ray) -> f32 {
+        HenyeyGreensteinPdf::phase(
+            self.g,
+            r_in.direction()
+                .unit_vector()
+                .dot(&scattered.direction().unit_vector()),
+        )
+    }
+}
@@ -1,14 +1,18 @@
 use super::*;
 
 pub struct Metal {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
     fuzz: f32,
 }
 
 impl Metal {
     pub fn new(a: Color, f: f32) -> Self {
+        Metal::from_texture(Arc::new(SolidColor::new(a)), f)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, f: f32) -> Self {
         Self {
-            albedo: a,
+            albedo,
             fuzz: match f < 1.0 {
                 true => f,
                 false => 1.0,
@@ -17,17 +21,37 @@ impl Metal {
     }
 }
 
-impl Material for Metal {
-    fn scatter(
-        &mut self,
-        r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool {
+impl Metal {
+    /// The glossy lobe about the mirror direction. Its exponent spreads about
+    /// as widely as offsetting the mirror direction by a random point in a
+    /// ball of radius `fuzz`.
+    fn lobe(&self, r_in: &Ray, rec: &HitRecord) -> PhongPdf {
         let reflected: Vec3 = Vec3::reflect(&r_in.direction().unit_vector(), &rec.normal);
-        *scattered = Ray::new(rec.p, reflected + self.fuzz * Vec3::random_in_unit_sphere());
-        *attenuation = self.albedo;
-        scattered.direction().dot(&rec.normal) > 0.0
+        PhongPdf::new(&reflected, 5.0 / (self.fuzz * self.fuzz))
+    }
+}
+
+impl Material for Metal {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, _rng: &mut Rng) -> Option<ScatterRecord> {
+        let attenuation: Color = self.albedo.value(rec.u, rec.v, &rec.p);
+        if self.fuzz <= 0.0 {
+            let reflected: Vec3 = Vec3::reflect(&r_in.direction().unit_vector(), &rec.normal);
+            return Some(ScatterRecord {
+                attenuation,
+                kind: ScatterKind::Specular(Ray::with_time(rec.p, reflected, r_in.time())),
+            });
+        }
+        Some(ScatterRecord {
+            attenuation,
+            kind: ScatterKind::Diffuse(Box::new(self.lobe(r_in, rec))),
+        })
+    }
+
+    /// The lobe, cut off below the surface where the light is absorbed.
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32 {
+        if self.fuzz <= 0.0 || scattered.direction().dot(&rec.normal) <= 0.0 {
+            return 0.0;
+        }
+        self.lobe(r_in, rec).value(&scattered.direction())
     }
 }
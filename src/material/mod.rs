@@ -1,28 +1,39 @@
 use crate::object::*;
+use crate::pdf::*;
 use crate::ray::*;
 use crate::rtweekend::*;
+use crate::texture::*;
 use crate::vec3::*;
 
-pub trait Material {
-    fn scatter(
-        &mut self,
-        r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
-    ) -> bool;
+use std::sync::Arc;
+
+pub struct ScatterRecord {
+    pub attenuation: Color,
+    pub kind: ScatterKind,
+}
+
+pub enum ScatterKind {
+    /// The one direction light leaves in, as off a mirror or through glass.
+    /// It is a discrete choice with no density.
+    Specular(Ray),
+    /// Light leaves in every direction, which the path samples from this
+    /// density. It must match `Material::scattering_pdf` wherever that is
+    /// non-zero, unless the attenuation already accounts for the difference.
+    Diffuse(Box<dyn Pdf>),
 }
 
-pub struct MaterialMock;
-impl Material for MaterialMock {
-    fn scatter(
-        &mut self,
-        _r_in: &Ray,
-        _rec: &HitRecord,
-        _attenuation: &mut Color,
-        _scattered: &mut Ray,
-    ) -> bool {
-        false
+pub trait Material: Send + Sync {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, rng: &mut Rng) -> Option<ScatterRecord>;
+
+    /// Density with which this material scatters `r_in` into `scattered`, for
+    /// weighting directions drawn from other densities such as the lights'.
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f32 {
+        0.0
+    }
+
+    /// Radiance the surface emits towards the origin of `r_in`.
+    fn emitted(&self, _r_in: &Ray, _rec: &HitRecord) -> Color {
+        Color::new(0.0, 0.0, 0.0)
     }
 }
 
@@ -33,9 +44,15 @@ pub fn schlick(cosine: f32, ref_idx: f32) -> f32 {
 }
 
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
 
 pub use dielectric::Dielectric;
+pub use diffuse_light::DiffuseLight;
+pub use henyey_greenstein::HenyeyGreenstein;
+pub use isotropic::Isotropic;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
@@ -0,0 +1,137 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    minimum: Point3,
+    maximum: Point3,
+}
+
+impl Aabb {
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Self {
+            minimum: Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z())),
+            maximum: Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z())),
+        }
+    }
+
+    /// Grows any side thinner than `delta` so that flat primitives, like an
+    /// axis-aligned triangle, still have a box the slab test can hit.
+    pub fn pad(&self, delta: f32) -> Aabb {
+        let mut minimum: [f32; 3] = [self.minimum.x(), self.minimum.y(), self.minimum.z()];
+        let mut maximum: [f32; 3] = [self.maximum.x(), self.maximum.y(), self.maximum.z()];
+        for a in 0..3 {
+            if maximum[a] - minimum[a] < delta {
+                minimum[a] -= delta / 2.0;
+                maximum[a] += delta / 2.0;
+            }
+        }
+        Aabb {
+            minimum: Point3::new(minimum[0], minimum[1], minimum[2]),
+            maximum: Point3::new(maximum[0], maximum[1], maximum[2]),
+        }
+    }
+
+    pub fn min(&self) -> Point3 {
+        self.minimum
+    }
+
+    pub fn max(&self) -> Point3 {
+        self.maximum
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.minimum + self.maximum)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d: Vec3 = self.maximum - self.minimum;
+        2.0 * (d.x() * d.y() + d.y() * d.z() + d.z() * d.x())
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let d: Vec3 = self.maximum - self.minimum;
+        if d.x() > d.y() && d.x() > d.z() {
+            0
+        } else if d.y() > d.z() {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn surrounding_box(box0: &Aabb, box1: &Aabb) -> Aabb {
+        Aabb {
+            minimum: Point3::new(
+                box0.minimum.x().min(box1.minimum.x()),
+                box0.minimum.y().min(box1.minimum.y()),
+                box0.minimum.z().min(box1.minimum.z()),
+            ),
+            maximum: Point3::new(
+                box0.maximum.x().max(box1.maximum.x()),
+                box0.maximum.y().max(box1.maximum.y()),
+                box0.maximum.z().max(box1.maximum.z()),
+            ),
+        }
+    }
+
+    pub fn hit(&self, r: &Ray, mut t_min: f32, mut t_max: f32) -> bool {
+        for a in 0..3 {
+            let inv_d: f32 = 1.0 / r.direction()[a];
+            let mut t0: f32 = (self.minimum[a] - r.origin()[a]) * inv_d;
+            let mut t1: f32 = (self.maximum[a] - r.origin()[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_orders_corners() {
+        let b: Aabb = Aabb::new(Point3::new(1.0, -1.0, 2.0), Point3::new(-1.0, 1.0, 0.0));
+        assert_eq!(b.min(), Point3::new(-1.0, -1.0, 0.0));
+        assert_eq!(b.max(), Point3::new(1.0, 1.0, 2.0));
+    }
+
+    #[test]
+    fn test_surrounding_box() {
+        let b0: Aabb = Aabb::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let b1: Aabb = Aabb::new(Point3::new(-1.0, 0.5, 0.5), Point3::new(0.5, 2.0, 0.5));
+        let b: Aabb = Aabb::surrounding_box(&b0, &b1);
+        assert_eq!(b.min(), Point3::new(-1.0, 0.0, 0.0));
+        assert_eq!(b.max(), Point3::new(1.0, 2.0, 1.0));
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let b: Aabb = Aabb::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.surface_area(), 22.0);
+        assert_eq!(b.longest_axis(), 2);
+    }
+
+    #[test]
+    fn test_hit() {
+        let b: Aabb = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let r: Ray = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(b.hit(&r, 0.0, f32::INFINITY));
+        assert!(!b.hit(&r, 0.0, 3.0));
+        let r: Ray = Ray::new(Point3::new(2.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(!b.hit(&r, 0.0, f32::INFINITY));
+        let r: Ray = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(!b.hit(&r, 0.0, f32::INFINITY));
+    }
+}
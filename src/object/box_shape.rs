@@ -0,0 +1,83 @@
+use super::*;
+use std::sync::Arc;
+
+/// An axis-aligned box made of six quads with outward-facing normals.
+pub struct BoxShape {
+    sides: HittableList,
+    bbox: Aabb,
+}
+
+impl BoxShape {
+    /// The box spanned by the opposite corners `a` and `b`.
+    pub fn new(a: Point3, b: Point3, mat_ptr: Arc<dyn Material>) -> Self {
+        let bbox: Aabb = Aabb::new(a, b);
+        let (min, max) = (bbox.min(), bbox.max());
+        let dx: Vec3 = Vec3::new(max.x() - min.x(), 0.0, 0.0);
+        let dy: Vec3 = Vec3::new(0.0, max.y() - min.y(), 0.0);
+        let dz: Vec3 = Vec3::new(0.0, 0.0, max.z() - min.z());
+
+        let mut sides: HittableList = HittableList::new();
+        let faces: [(Point3, Vec3, Vec3); 6] = [
+            (Point3::new(min.x(), min.y(), max.z()), dx, dy),
+            (Point3::new(max.x(), min.y(), max.z()), -dz, dy),
+            (Point3::new(max.x(), min.y(), min.z()), -dx, dy),
+            (Point3::new(min.x(), min.y(), min.z()), dz, dy),
+            (Point3::new(min.x(), max.y(), max.z()), dx, -dz),
+            (Point3::new(min.x(), min.y(), min.z()), dx, dz),
+        ];
+        for (q, u, v) in faces {
+            sides.add(Arc::new(Quad::new(q, u, v, mat_ptr.clone())));
+        }
+        Self { sides, bbox }
+    }
+}
+
+impl Hittable for BoxShape {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>> {
+        self.sides.hit(r, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bbox.pad(1e-4))
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::INFINITY;
+
+    #[test]
+    fn test_normals_point_outwards() {
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let shape: BoxShape = BoxShape::new(
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(-1.0, -1.0, -1.0),
+            mat,
+        );
+        let axes: [Vec3; 3] = [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        for axis in axes {
+            for side in [axis, -axis] {
+                let r: Ray = Ray::new(5.0 * side, -side);
+                let rec: HitRecord = shape.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+                assert_eq!(rec.t, 4.0);
+                assert!(rec.front_face, "{:?}", side);
+                assert_eq!(rec.normal, side);
+            }
+        }
+
+        // From inside, the far wall is hit on its back.
+        let r: Ray = Ray::new(Point3::default(), Vec3::new(0.0, 0.0, 1.0));
+        let rec: HitRecord = shape.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+        assert!(!rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, -1.0));
+    }
+}
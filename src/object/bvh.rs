@@ -0,0 +1,314 @@
+use super::*;
+use std::sync::Arc;
+
+const SAH_BUCKETS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+const TRAVERSAL_COST: f32 = 0.125;
+
+pub struct BvhNode {
+    left: Arc<dyn Hittable>,
+    right: Arc<dyn Hittable>,
+    bbox: Aabb,
+}
+
+struct Primitive {
+    object: Arc<dyn Hittable>,
+    bbox: Aabb,
+    centroid: Point3,
+}
+
+#[derive(Clone, Copy)]
+struct Bucket {
+    count: usize,
+    bbox: Option<Aabb>,
+}
+
+/// Puts every bounded object of `list` into one BVH. Objects without a bounding
+/// box, such as infinite planes, cannot be placed in the tree and are tested
+/// alongside it instead.
+pub fn build_bvh(list: HittableList) -> HittableList {
+    let mut bounded: HittableList = HittableList::new();
+    let mut world: HittableList = HittableList::new();
+    for object in list.into_objects() {
+        match object.bounding_box() {
+            Some(_) => bounded.add(object),
+            None => world.add(object),
+        }
+    }
+    if !bounded.is_empty() {
+        world.add(Arc::new(BvhNode::new(bounded)));
+    }
+    world
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let primitives: Vec<Primitive> = list
+            .into_objects()
+            .into_iter()
+            .map(|object| {
+                let bbox: Aabb = object
+                    .bounding_box()
+                    .expect("No bounding box in BvhNode constructor.");
+                Primitive {
+                    object,
+                    bbox,
+                    centroid: bbox.centroid(),
+                }
+            })
+            .collect();
+        assert!(!primitives.is_empty(), "BvhNode needs at least one object.");
+        Self::split(primitives)
+    }
+
+    fn build(mut primitives: Vec<Primitive>) -> Arc<dyn Hittable> {
+        if primitives.len() == 1 {
+            return primitives.remove(0).object;
+        }
+
+        let bbox: Aabb = Self::bounds(&primitives, |p| p.bbox);
+        let split: Option<(f32, usize, f32)> = Self::best_split(&primitives);
+        let leaf_is_cheaper: bool = match split {
+            Some((cost, _, _)) => {
+                primitives.len() as f32 <= TRAVERSAL_COST + cost / bbox.surface_area()
+            }
+            None => true,
+        };
+
+        if primitives.len() <= MAX_LEAF_SIZE && leaf_is_cheaper {
+            let mut leaf: HittableList = HittableList::new();
+            for p in primitives {
+                leaf.add(p.object);
+            }
+            return Arc::new(leaf);
+        }
+        Arc::new(Self::split(primitives))
+    }
+
+    fn split(mut primitives: Vec<Primitive>) -> Self {
+        if primitives.len() == 1 {
+            let p: Primitive = primitives.remove(0);
+            return Self {
+                left: p.object.clone(),
+                right: p.object,
+                bbox: p.bbox,
+            };
+        }
+
+        let bbox: Aabb = Self::bounds(&primitives, |p| p.bbox);
+        let mid: usize = match Self::best_split(&primitives) {
+            Some((_, axis, threshold)) => {
+                let (mut left, right): (Vec<Primitive>, Vec<Primitive>) = primitives
+                    .into_iter()
+                    .partition(|p| p.centroid[axis] < threshold);
+                let mid: usize = left.len();
+                left.extend(right);
+                primitives = left;
+                mid
+            }
+            None => {
+                // All centroids coincide, so no plane separates them: halve the
+                // list instead to keep the tree balanced.
+                primitives.len() / 2
+            }
+        };
+
+        let right: Vec<Primitive> = primitives.split_off(mid);
+        Self {
+            left: Self::build(primitives),
+            right: Self::build(right),
+            bbox,
+        }
+    }
+
+    /// Evaluates the surface area heuristic over `SAH_BUCKETS` centroid buckets on
+    /// every axis and returns the cheapest `(cost, axis, threshold)` plane, if any
+    /// plane actually separates the primitives. The cost is not yet normalised by
+    /// the parent's surface area.
+    fn best_split(primitives: &[Primitive]) -> Option<(f32, usize, f32)> {
+        let centroid_bounds: Aabb = Self::bounds(primitives, |p| Aabb::new(p.centroid, p.centroid));
+        let mut best: Option<(f32, usize, f32)> = None;
+
+        for axis in Self::axes_by_extent(&centroid_bounds).iter().copied() {
+            let lo: f32 = centroid_bounds.min()[axis];
+            let extent: f32 = centroid_bounds.max()[axis] - lo;
+            if extent <= 0.0 {
+                continue;
+            }
+
+            let mut buckets: [Bucket; SAH_BUCKETS] = [Bucket {
+                count: 0,
+                bbox: None,
+            }; SAH_BUCKETS];
+            for p in primitives {
+                let b: usize = Self::bucket_index(p.centroid[axis], lo, extent);
+                buckets[b].count += 1;
+                buckets[b].bbox = Some(Self::grow(buckets[b].bbox, &p.bbox));
+            }
+
+            for i in 1..SAH_BUCKETS {
+                let (left_count, left_area) = Self::bucket_cost(&buckets[..i]);
+                let (right_count, right_area) = Self::bucket_cost(&buckets[i..]);
+                if left_count == 0 || right_count == 0 {
+                    continue;
+                }
+                let cost: f32 = left_count as f32 * left_area + right_count as f32 * right_area;
+                if best.is_none_or(|(c, _, _)| cost < c) {
+                    best = Some((cost, axis, lo + extent * i as f32 / SAH_BUCKETS as f32));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Longest axis first, so that ties in cost favour the widest spread.
+    fn axes_by_extent(bounds: &Aabb) -> [usize; 3] {
+        match bounds.longest_axis() {
+            0 => [0, 1, 2],
+            1 => [1, 2, 0],
+            _ => [2, 0, 1],
+        }
+    }
+
+    fn bucket_index(c: f32, lo: f32, extent: f32) -> usize {
+        let b: usize = (SAH_BUCKETS as f32 * (c - lo) / extent) as usize;
+        b.min(SAH_BUCKETS - 1)
+    }
+
+    fn bucket_cost(buckets: &[Bucket]) -> (usize, f32) {
+        let mut count: usize = 0;
+        let mut bbox: Option<Aabb> = None;
+        for b in buckets {
+            count += b.count;
+            if let Some(bb) = b.bbox {
+                bbox = Some(Self::grow(bbox, &bb));
+            }
+        }
+        (count, bbox.map_or(0.0, |b| b.surface_area()))
+    }
+
+    fn grow(bbox: Option<Aabb>, other: &Aabb) -> Aabb {
+        match bbox {
+            Some(b) => Aabb::surrounding_box(&b, other),
+            None => *other,
+        }
+    }
+
+    fn bounds<F: Fn(&Primitive) -> Aabb>(primitives: &[Primitive], f: F) -> Aabb {
+        primitives.iter().skip(1).fold(f(&primitives[0]), |acc, p| {
+            Aabb::surrounding_box(&acc, &f(p))
+        })
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>> {
+        if !self.bbox.hit(r, t_min, t_max) {
+            return None;
+        }
+
+        let hit_left: Option<HitRecord> = self.left.hit(r, t_min, t_max, rng);
+        let t_max: f32 = hit_left.as_ref().map_or(t_max, |rec| rec.t);
+        self.right.hit(r, t_min, t_max, rng).or(hit_left)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::INFINITY;
+
+    fn grid_of_spheres() -> HittableList {
+        let mut list: HittableList = HittableList::new();
+        for a in -5..5 {
+            for b in -5..5 {
+                list.add(Arc::new(Sphere::new(
+                    Point3::new(a as f32, 0.0, b as f32),
+                    0.3,
+                    Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+                )));
+            }
+        }
+        list
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bvh: BvhNode = BvhNode::new(grid_of_spheres());
+        let bbox: Aabb = bvh.bounding_box().unwrap();
+        assert_eq!(bbox.min(), Point3::new(-5.3, -0.3, -5.3));
+        assert_eq!(bbox.max(), Point3::new(4.3, 0.3, 4.3));
+    }
+
+    #[test]
+    fn test_single_object() {
+        let mut list: HittableList = HittableList::new();
+        list.add(Arc::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        let bvh: BvhNode = BvhNode::new(list);
+        let r: Ray = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            bvh.hit(&r, 0.001, INFINITY, &mut Rng::new(0))
+                .map(|rec| rec.t),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn test_matches_linear_list() {
+        let list: HittableList = grid_of_spheres();
+        let bvh: BvhNode = BvhNode::new(grid_of_spheres());
+
+        for i in 0..50 {
+            let x: f32 = -5.0 + i as f32 * 0.21;
+            let r: Ray = Ray::new(Point3::new(x, 3.0, -8.0), Vec3::new(0.1, -0.35, 1.0));
+            let expected: Option<HitRecord> = list.hit(&r, 0.001, INFINITY, &mut Rng::new(0));
+            let actual: Option<HitRecord> = bvh.hit(&r, 0.001, INFINITY, &mut Rng::new(0));
+            assert_eq!(expected.is_some(), actual.is_some());
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                assert_eq!(expected.t, actual.t);
+                assert_eq!(expected.p, actual.p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_bvh_keeps_unbounded_objects() {
+        let mut list: HittableList = grid_of_spheres();
+        list.add(Arc::new(Plane::new(
+            Point3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        let world: HittableList = build_bvh(list);
+        assert!(world.bounding_box().is_none());
+
+        // Straight down between the spheres only the plane is in the way.
+        let r: Ray = Ray::new(Point3::new(0.5, 5.0, 0.5), Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(
+            world
+                .hit(&r, 0.001, INFINITY, &mut Rng::new(0))
+                .map(|rec| rec.t),
+            Some(6.0)
+        );
+        let r: Ray = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(
+            world
+                .hit(&r, 0.001, INFINITY, &mut Rng::new(0))
+                .map(|rec| rec.t),
+            Some(4.7)
+        );
+    }
+}
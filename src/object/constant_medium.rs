@@ -0,0 +1,128 @@
+use super::*;
+use crate::texture::Texture;
+use std::sync::Arc;
+
+/// A uniform fog or smoke filling the inside of `boundary`, which must be a
+/// closed convex shape such as a sphere or a box. A ray travelling a distance
+/// `d` through it scatters with probability `1 - exp(-density * d)`.
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable>,
+    neg_inv_density: f32,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Arc<dyn Hittable>, density: f32, albedo: Color) -> Self {
+        ConstantMedium::with_phase_function(boundary, density, Arc::new(Isotropic::new(albedo)))
+    }
+
+    pub fn from_texture(
+        boundary: Arc<dyn Hittable>,
+        density: f32,
+        albedo: Arc<dyn Texture>,
+    ) -> Self {
+        ConstantMedium::with_phase_function(
+            boundary,
+            density,
+            Arc::new(Isotropic::from_texture(albedo)),
+        )
+    }
+
+    pub fn with_phase_function(
+        boundary: Arc<dyn Hittable>,
+        density: f32,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        assert!(density > 0.0, "ConstantMedium density must be positive.");
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>> {
+        // Find where the ray enters and leaves the boundary, even if it starts
+        // inside, then clip that span to the requested interval.
+        let entry: HitRecord = self.boundary.hit(r, -INFINITY, INFINITY, rng)?;
+        let exit: HitRecord = self.boundary.hit(r, entry.t + 0.0001, INFINITY, rng)?;
+        let t_enter: f32 = entry.t.max(t_min).max(0.0);
+        let t_exit: f32 = exit.t.min(t_max);
+        if t_enter >= t_exit {
+            return None;
+        }
+
+        let ray_length: f32 = r.direction().length();
+        let distance_inside_boundary: f32 = (t_exit - t_enter) * ray_length;
+        let hit_distance: f32 = self.neg_inv_density * rng.random_double().ln();
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t: f32 = t_enter + hit_distance / ray_length;
+        // Normal and facing mean nothing inside a volume; any fixed values do.
+        Some(HitRecord {
+            p: r.at(t),
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            t,
+            front_face: true,
+            mat_ptr: self.phase_function.as_ref(),
+            u: 0.0,
+            v: 0.0,
+            barycentric: [0.0; 3],
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_sphere_of_fog(density: f32) -> ConstantMedium {
+        let boundary: Arc<dyn Hittable> = Arc::new(Sphere::new(
+            Point3::default(),
+            1.0,
+            Arc::new(Lambertian::new(Color::new(0.0, 0.0, 0.0))),
+        ));
+        ConstantMedium::new(boundary, density, Color::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn test_scatter_probability() {
+        // Crossing 2 units at density 0.5 scatters with probability 1 - e^-1.
+        let medium: ConstantMedium = unit_sphere_of_fog(0.5);
+        let r: Ray = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut rng: Rng = Rng::new(7);
+        let trials: usize = 20000;
+        let mut hits: usize = 0;
+        for _ in 0..trials {
+            if let Some(rec) = medium.hit(&r, 0.001, INFINITY, &mut rng) {
+                assert!(rec.t >= 4.0 && rec.t <= 6.0);
+                hits += 1;
+            }
+        }
+        let expected: f32 = 1.0 - (-1.0f32).exp();
+        assert!((hits as f32 / trials as f32 - expected).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_ray_starting_inside() {
+        let medium: ConstantMedium = unit_sphere_of_fog(1e6);
+        let r: Ray = Ray::new(Point3::default(), Vec3::new(0.0, 2.0, 0.0));
+        let rec: HitRecord = medium.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+        assert!(rec.t > 0.0 && rec.t < 0.01);
+        // Nothing is left of the medium beyond t_max.
+        let far: Ray = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(medium.hit(&far, 0.001, 3.0, &mut Rng::new(0)).is_none());
+    }
+}
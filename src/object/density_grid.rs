@@ -0,0 +1,187 @@
+use super::*;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+/// Densities sampled on a regular voxel grid spanning the unit cube, stored
+/// with x varying fastest, then y, then z.
+pub struct DensityGrid {
+    resolution: [usize; 3],
+    data: Vec<f32>,
+    max_density: f32,
+}
+
+impl DensityGrid {
+    pub fn new(resolution: [usize; 3], data: Vec<f32>) -> Self {
+        assert!(
+            resolution.iter().all(|&n| n > 0),
+            "DensityGrid needs at least one voxel on every axis."
+        );
+        assert_eq!(
+            data.len(),
+            resolution[0] * resolution[1] * resolution[2],
+            "DensityGrid data does not match its resolution."
+        );
+        assert!(
+            data.iter().all(|&d| d >= 0.0),
+            "DensityGrid densities must be non-negative."
+        );
+        let max_density: f32 = data.iter().copied().fold(0.0, f32::max);
+        Self {
+            resolution,
+            data,
+            max_density,
+        }
+    }
+
+    /// Reads a headerless grid of little-endian 32-bit floats, x fastest.
+    pub fn read_raw<R: Read>(mut reader: R, resolution: [usize; 3]) -> io::Result<Self> {
+        let count: usize = resolution[0] * resolution[1] * resolution[2];
+        let mut bytes: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        if count == 0 || bytes.len() != count * 4 {
+            return Err(invalid_data(format!(
+                "expected {} bytes for a {}x{}x{} grid, found {}",
+                count * 4,
+                resolution[0],
+                resolution[1],
+                resolution[2],
+                bytes.len()
+            )));
+        }
+        let data: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        if data.iter().any(|d| !(d.is_finite() && *d >= 0.0)) {
+            return Err(invalid_data(
+                "densities must be finite and non-negative".to_string(),
+            ));
+        }
+        Ok(DensityGrid::new(resolution, data))
+    }
+
+    pub fn load_raw(path: &Path, resolution: [usize; 3]) -> io::Result<Self> {
+        DensityGrid::read_raw(BufReader::new(File::open(path)?), resolution)
+    }
+
+    /// A billowing puff of cloud: fractal noise of frequency `scale` that fades
+    /// out towards the faces of the cube so the grid's edges never show.
+    pub fn from_noise(resolution: [usize; 3], seed: u64, scale: f32, octaves: u32) -> Self {
+        let noise: Perlin = Perlin::new(seed);
+        let mut data: Vec<f32> = Vec::with_capacity(resolution[0] * resolution[1] * resolution[2]);
+        for k in 0..resolution[2] {
+            for j in 0..resolution[1] {
+                for i in 0..resolution[0] {
+                    let p: Point3 = Point3::new(
+                        (i as f32 + 0.5) / resolution[0] as f32,
+                        (j as f32 + 0.5) / resolution[1] as f32,
+                        (k as f32 + 0.5) / resolution[2] as f32,
+                    );
+                    let falloff: f32 = (2.0 * (p - Point3::new(0.5, 0.5, 0.5))).length_squared();
+                    let density: f32 = 0.5 + noise.fbm(&(scale * p), octaves) - falloff;
+                    data.push(density.clamp(0.0, 1.0));
+                }
+            }
+        }
+        DensityGrid::new(resolution, data)
+    }
+
+    pub fn resolution(&self) -> [usize; 3] {
+        self.resolution
+    }
+
+    /// The largest density anywhere in the grid, which bounds `density`.
+    pub fn max_density(&self) -> f32 {
+        self.max_density
+    }
+
+    /// Trilinear interpolation between voxel centres at `p` in the unit cube;
+    /// zero outside it.
+    pub fn density(&self, p: &Point3) -> f32 {
+        if (0..3).any(|a| !(0.0..=1.0).contains(&p[a])) {
+            return 0.0;
+        }
+        let mut lo: [usize; 3] = [0; 3];
+        let mut hi: [usize; 3] = [0; 3];
+        let mut frac: [f32; 3] = [0.0; 3];
+        for a in 0..3 {
+            let n: usize = self.resolution[a];
+            let x: f32 = (p[a] * n as f32 - 0.5).clamp(0.0, (n - 1) as f32);
+            lo[a] = x.floor() as usize;
+            hi[a] = (lo[a] + 1).min(n - 1);
+            frac[a] = x - lo[a] as f32;
+        }
+
+        let mut accum: f32 = 0.0;
+        for corner in 0..8 {
+            let mut weight: f32 = 1.0;
+            let mut index: [usize; 3] = [0; 3];
+            for a in 0..3 {
+                if corner & (1 << a) != 0 {
+                    index[a] = hi[a];
+                    weight *= frac[a];
+                } else {
+                    index[a] = lo[a];
+                    weight *= 1.0 - frac[a];
+                }
+            }
+            accum += weight * self.voxel(index);
+        }
+        accum
+    }
+
+    fn voxel(&self, [i, j, k]: [usize; 3]) -> f32 {
+        self.data[i + self.resolution[0] * (j + self.resolution[1] * k)]
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trilinear_density() {
+        let grid: DensityGrid = DensityGrid::new([2, 1, 1], vec![0.0, 4.0]);
+        assert_eq!(grid.max_density(), 4.0);
+        assert_eq!(grid.density(&Point3::new(0.25, 0.5, 0.5)), 0.0);
+        assert_eq!(grid.density(&Point3::new(0.5, 0.5, 0.5)), 2.0);
+        assert_eq!(grid.density(&Point3::new(0.9, 0.1, 0.7)), 4.0);
+        assert_eq!(grid.density(&Point3::new(1.1, 0.5, 0.5)), 0.0);
+    }
+
+    #[test]
+    fn test_read_raw() {
+        let bytes: Vec<u8> = [0.5f32, 1.0, 2.0, 0.0]
+            .iter()
+            .flat_map(|d| d.to_le_bytes())
+            .collect();
+        let grid: DensityGrid = DensityGrid::read_raw(io::Cursor::new(&bytes), [1, 2, 2]).unwrap();
+        assert_eq!(grid.resolution(), [1, 2, 2]);
+        assert_eq!(grid.max_density(), 2.0);
+        assert_eq!(grid.density(&Point3::new(0.5, 0.75, 0.25)), 1.0);
+
+        let err: io::Error = DensityGrid::read_raw(io::Cursor::new(&bytes), [2, 2, 2])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "expected 32 bytes for a 2x2x2 grid, found 16"
+        );
+    }
+
+    #[test]
+    fn test_noise_fades_at_the_faces() {
+        let grid: DensityGrid = DensityGrid::from_noise([16, 16, 16], 5, 3.0, 4);
+        assert!(grid.max_density() > 0.0);
+        assert_eq!(grid.density(&Point3::new(0.0, 0.0, 0.0)), 0.0);
+    }
+}
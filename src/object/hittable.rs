@@ -1,5 +1,20 @@
 use super::*;
 
-pub trait Hittable {
-    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rec: &mut HitRecord) -> bool;
+pub trait Hittable: Send + Sync {
+    /// The nearest intersection with `r` between `t_min` and `t_max`. Only
+    /// participating media draw on `rng`, to pick where light scatters.
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>>;
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    /// Solid-angle density, as seen from `origin`, with which `random` picks
+    /// `direction`. Only shapes that can be sampled as lights override it.
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    /// A direction from `origin` towards the point of the shape that `u`, a
+    /// point of the unit square, maps to; uniform `u` gives random points.
+    fn random(&self, _origin: &Point3, _u: (f32, f32)) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
 }
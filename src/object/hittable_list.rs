@@ -1,39 +1,76 @@
 use super::*;
-use std::rc::Rc;
+use std::sync::Arc;
 
+#[derive(Default)]
 pub struct HittableList {
-    objects: Vec<Rc<dyn Hittable>>,
+    objects: Vec<Arc<dyn Hittable>>,
 }
 
 impl HittableList {
     pub fn new() -> Self {
-        let v: Vec<Rc<dyn Hittable>> = Vec::new();
+        let v: Vec<Arc<dyn Hittable>> = Vec::new();
         Self { objects: v }
     }
 
-    pub fn clear(&mut self) {
-        self.objects.clear();
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
     }
 
-    pub fn add(&mut self, object: Rc<dyn Hittable>) {
+    pub fn into_objects(self) -> Vec<Arc<dyn Hittable>> {
+        self.objects
+    }
+
+    pub fn add(&mut self, object: Arc<dyn Hittable>) {
         self.objects.push(object);
     }
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rec: &mut HitRecord) -> bool {
-        let mut temp_rec: HitRecord = HitRecord::default();
-        let mut hit_anything: bool = false;
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>> {
+        let mut closest: Option<HitRecord> = None;
         let mut closest_so_far: f32 = t_max;
 
         for object in self.objects.iter() {
-            if object.hit(r, t_min, closest_so_far, &mut temp_rec) {
-                hit_anything = true;
-                closest_so_far = temp_rec.clone().t;
-                *rec = temp_rec.clone();
+            if let Some(rec) = object.hit(r, t_min, closest_so_far, rng) {
+                closest_so_far = rec.t;
+                closest = Some(rec);
             }
         }
 
-        hit_anything
+        closest
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut output_box: Option<Aabb> = None;
+        for object in self.objects.iter() {
+            let temp_box: Aabb = object.bounding_box()?;
+            output_box = Some(match output_box {
+                Some(b) => Aabb::surrounding_box(&b, &temp_box),
+                None => temp_box,
+            });
+        }
+        output_box
+    }
+
+    /// Every object is equally likely to be sampled.
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let sum: f32 = self
+            .objects
+            .iter()
+            .map(|object| object.pdf_value(origin, direction))
+            .sum();
+        sum / self.objects.len() as f32
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        // The slice of `u.0` that picks an object is stretched back over [0, 1)
+        // for the object itself.
+        let n: f32 = self.objects.len() as f32;
+        let i: usize = ((u.0 * n) as usize).min(self.objects.len() - 1);
+        let u0: f32 = (u.0 * n - i as f32).clamp(0.0, 1.0 - f32::EPSILON);
+        self.objects[i].random(origin, (u0, u.1))
     }
 }
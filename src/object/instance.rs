@@ -0,0 +1,130 @@
+use super::*;
+use std::sync::Arc;
+
+/// Places a shared hittable in the world with an affine transform, so one
+/// object, such as a large mesh, can appear many times at different poses.
+pub struct Instance {
+    object: Arc<dyn Hittable>,
+    transform: Transform,
+    bbox: Option<Aabb>,
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Hittable>, transform: Transform) -> Self {
+        let bbox: Option<Aabb> = object.bounding_box().map(|b| transform_box(&b, &transform));
+        Self {
+            object,
+            transform,
+            bbox,
+        }
+    }
+}
+
+/// The world-space box around all eight transformed corners of `b`.
+pub(crate) fn transform_box(b: &Aabb, transform: &Transform) -> Aabb {
+    let (min, max) = (b.min(), b.max());
+    let corners: Vec<Point3> = (0..8)
+        .map(|i| {
+            transform.transform_point(&Point3::new(
+                if i & 1 == 0 { min.x() } else { max.x() },
+                if i & 2 == 0 { min.y() } else { max.y() },
+                if i & 4 == 0 { min.z() } else { max.z() },
+            ))
+        })
+        .collect();
+    corners[1..]
+        .iter()
+        .fold(Aabb::new(corners[0], corners[0]), |acc, p| {
+            Aabb::surrounding_box(&acc, &Aabb::new(*p, *p))
+        })
+}
+
+/// Intersects `object` as if it had been moved into the world by `transform`.
+pub(crate) fn hit_transformed<'a>(
+    object: &'a dyn Hittable,
+    transform: &Transform,
+    r: &Ray,
+    t_min: f32,
+    t_max: f32,
+    rng: &mut Rng,
+) -> Option<HitRecord<'a>> {
+    // The object-space direction is not renormalized, so `t` is the same
+    // in both spaces.
+    let inverse: Transform = transform.inverse();
+    let object_ray: Ray = Ray::with_time(
+        inverse.transform_point(&r.origin()),
+        inverse.transform_vector(&r.direction()),
+        r.time(),
+    );
+    let mut rec: HitRecord = object.hit(&object_ray, t_min, t_max, rng)?;
+
+    // The inverse transpose keeps the sign of the normal's dot product with
+    // the ray direction, so `front_face` carries over unchanged.
+    rec.p = transform.transform_point(&rec.p);
+    rec.normal = transform.transform_normal(&rec.normal).unit_vector();
+    Some(rec)
+}
+
+impl Hittable for Instance {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>> {
+        hit_transformed(self.object.as_ref(), &self.transform, r, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bbox
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::INFINITY;
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn test_translated_and_scaled_sphere() {
+        let sphere: Arc<dyn Hittable> = Arc::new(Sphere::new(Point3::default(), 1.0, material()));
+        let instance: Instance = Instance::new(
+            sphere,
+            Transform::translate(Vec3::new(0.0, 0.0, -5.0))
+                * Transform::scale(Vec3::new(1.0, 1.0, 2.0)),
+        );
+        let r: Ray = Ray::new(Point3::default(), Vec3::new(0.0, 0.0, -1.0));
+        let rec: HitRecord = instance.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+        assert_eq!(rec.t, 3.0);
+        assert_eq!(rec.p, Point3::new(0.0, 0.0, -3.0));
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert!(rec.front_face);
+
+        let bbox: Aabb = instance.bounding_box().unwrap();
+        assert_eq!(bbox.min(), Point3::new(-1.0, -1.0, -7.0));
+        assert_eq!(bbox.max(), Point3::new(1.0, 1.0, -3.0));
+    }
+
+    #[test]
+    fn test_rotated_box() {
+        let shape: Arc<dyn Hittable> = Arc::new(BoxShape::new(
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            material(),
+        ));
+        let instance: Instance = Instance::new(shape, Transform::rotate_y(45.0));
+        // A corner now points straight at +x.
+        let r: Ray = Ray::new(Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec: HitRecord = instance.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+        assert!((rec.t - (5.0 - 2f32.sqrt())).abs() < 1e-4);
+        assert!((rec.normal.length() - 1.0).abs() < 1e-6);
+        assert!(rec.normal.dot(&r.direction()) < 0.0);
+
+        let bbox: Aabb = instance.bounding_box().unwrap();
+        assert!((bbox.max().x() - 2f32.sqrt()).abs() < 1e-3);
+        assert!((bbox.max().y() - 1.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,252 @@
+use super::instance::{hit_transformed, transform_box};
+use super::*;
+use std::sync::Arc;
+
+/// Bounding box samples taken between each pair of keyframes.
+const BOX_SAMPLES: usize = 32;
+
+/// A pose at one moment: the object is scaled, then rotated by `degrees`
+/// about `axis`, then translated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub translate: Vec3,
+    pub axis: Vec3,
+    pub degrees: f32,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, translate: Vec3) -> Self {
+        Self {
+            time,
+            translate,
+            axis: Vec3::new(0.0, 1.0, 0.0),
+            degrees: 0.0,
+            scale: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_rotation(mut self, axis: Vec3, degrees: f32) -> Self {
+        self.axis = axis;
+        self.degrees = degrees;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The rotation as a unit quaternion `[x, y, z, w]`.
+    fn quaternion(&self) -> [f32; 4] {
+        let (sin, cos) = (degree_to_radians(self.degrees) / 2.0).sin_cos();
+        let a: Vec3 = self.axis.unit_vector();
+        [a.x() * sin, a.y() * sin, a.z() * sin, cos]
+    }
+}
+
+/// An instance whose transform is interpolated between keyframes at the time
+/// each ray was cast: translation and scale linearly, rotation along the
+/// shortest arc. Before the first and after the last keyframe it holds still.
+pub struct KeyframedInstance {
+    object: Arc<dyn Hittable>,
+    keyframes: Vec<Keyframe>,
+    bbox: Option<Aabb>,
+}
+
+impl KeyframedInstance {
+    pub fn new(object: Arc<dyn Hittable>, mut keyframes: Vec<Keyframe>) -> Self {
+        assert!(!keyframes.is_empty(), "KeyframedInstance needs a keyframe.");
+        assert!(
+            keyframes.iter().all(|k| k.axis.length_squared() > 0.0
+                && k.scale.x() != 0.0
+                && k.scale.y() != 0.0
+                && k.scale.z() != 0.0),
+            "Keyframe axes and scale factors must not be zero."
+        );
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let mut instance: KeyframedInstance = Self {
+            object,
+            keyframes,
+            bbox: None,
+        };
+        instance.bbox = instance.sweep_box();
+        instance
+    }
+
+    pub fn transform_at(&self, time: f32) -> Transform {
+        let last: usize = self.keyframes.len() - 1;
+        let next: usize = self.keyframes.partition_point(|k| k.time <= time);
+        let (a, b, s) = if next == 0 {
+            (0, 0, 0.0)
+        } else if next > last {
+            (last, last, 0.0)
+        } else {
+            let (a, b) = (&self.keyframes[next - 1], &self.keyframes[next]);
+            (next - 1, next, (time - a.time) / (b.time - a.time))
+        };
+        interpolate(&self.keyframes[a], &self.keyframes[b], s)
+    }
+
+    /// The box swept by the object over every keyframe segment. Rotations move
+    /// corners along arcs, so the sampled boxes are padded a little.
+    fn sweep_box(&self) -> Option<Aabb> {
+        let object_box: Aabb = self.object.bounding_box()?;
+        let mut sweep: Aabb =
+            transform_box(&object_box, &self.transform_at(self.keyframes[0].time));
+        for pair in self.keyframes.windows(2) {
+            for i in 1..=BOX_SAMPLES {
+                let s: f32 = i as f32 / BOX_SAMPLES as f32;
+                let t: Transform = interpolate(&pair[0], &pair[1], s);
+                sweep = Aabb::surrounding_box(&sweep, &transform_box(&object_box, &t));
+            }
+        }
+        let margin: f32 = 1e-3 * (sweep.max() - sweep.min()).length();
+        let m: Vec3 = Vec3::new(margin, margin, margin);
+        Some(Aabb::new(sweep.min() - m, sweep.max() + m))
+    }
+}
+
+fn interpolate(a: &Keyframe, b: &Keyframe, s: f32) -> Transform {
+    let translate: Vec3 = a.translate + s * (b.translate - a.translate);
+    let scale: Vec3 = a.scale + s * (b.scale - a.scale);
+    let [x, y, z, w] = slerp(a.quaternion(), b.quaternion(), s);
+    let sin_half: f32 = (1.0 - w * w).max(0.0).sqrt();
+    let rotation: Transform = if sin_half < 1e-6 {
+        Transform::identity()
+    } else {
+        let degrees: f32 = 2.0 * w.clamp(-1.0, 1.0).acos() * 180.0 / PI;
+        Transform::rotate(Vec3::new(x, y, z) / sin_half, degrees)
+    };
+    Transform::translate(translate) * rotation * Transform::scale(scale)
+}
+
+fn slerp(a: [f32; 4], mut b: [f32; 4], s: f32) -> [f32; 4] {
+    let mut dot: f32 = (0..4).map(|i| a[i] * b[i]).sum();
+    // q and -q are the same rotation; pick the one on the shorter arc.
+    if dot < 0.0 {
+        b = b.map(|c| -c);
+        dot = -dot;
+    }
+    let (wa, wb) = if dot > 0.9995 {
+        (1.0 - s, s)
+    } else {
+        let theta: f32 = dot.acos();
+        (
+            ((1.0 - s) * theta).sin() / theta.sin(),
+            (s * theta).sin() / theta.sin(),
+        )
+    };
+    let q: [f32; 4] = [0, 1, 2, 3].map(|i| wa * a[i] + wb * b[i]);
+    let length: f32 = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+    q.map(|c| c / length)
+}
+
+impl Hittable for KeyframedInstance {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rng: &mut Rng) -> Option<HitRecord<'_>> {
+        hit_transformed(
+            self.object.as_ref(),
+            &self.transform_at(r.time()),
+            r,
+            t_min,
+            t_max,
+            rng,
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bbox
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    fn unit_box() -> Arc<dyn Hittable> {
+        Arc::new(BoxShape::new(
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        ))
+    }
+
+    #[test]
+    fn test_interpolates_and_holds() {
+        let instance: KeyframedInstance = KeyframedInstance::new(
+            unit_box(),
+            vec![
+                Keyframe::new(1.0, Vec3::new(4.0, 0.0, 0.0))
+                    .with_rotation(Vec3::new(0.0, 1.0, 0.0), 120.0),
+                Keyframe::new(0.0, Vec3::new(0.0, 0.0, 0.0)),
+            ],
+        );
+        let p: Point3 = Point3::new(1.0, 0.0, 0.0);
+        assert_close(instance.transform_at(-1.0).transform_point(&p), p);
+        // Halfway: moved by 2 and turned 60 degrees about y.
+        let half_sqrt3: f32 = 3f32.sqrt() / 2.0;
+        assert_close(
+            instance.transform_at(0.5).transform_point(&p),
+            Point3::new(2.5, 0.0, -half_sqrt3),
+        );
+        assert_close(
+            instance.transform_at(5.0).transform_point(&p),
+            Point3::new(3.5, 0.0, -half_sqrt3),
+        );
+    }
+
+    #[test]
+    fn test_bounding_box_covers_motion() {
+        let instance: KeyframedInstance = KeyframedInstance::new(
+            unit_box(),
+            vec![
+                Keyframe::new(0.0, Vec3::new(0.0, 0.0, 0.0)),
+                Keyframe::new(1.0, Vec3::new(0.0, 0.0, 0.0))
+                    .with_rotation(Vec3::new(0.0, 1.0, 0.0), 90.0),
+            ],
+        );
+        let bbox: Aabb = instance.bounding_box().unwrap();
+        // At 45 degrees a corner reaches sqrt(2) along x.
+        assert!(bbox.max().x() >= 2f32.sqrt());
+        assert!(bbox.max().x() < 1.45);
+    }
+
+    #[test]
+    fn test_hit_uses_ray_time() {
+        let instance: KeyframedInstance = KeyframedInstance::new(
+            unit_box(),
+            vec![
+                Keyframe::new(0.0, Vec3::new(0.0, 0.0, 0.0)),
+                Keyframe::new(1.0, Vec3::new(0.0, 10.0, 0.0)),
+            ],
+        );
+        let origin: Point3 = Point3::new(0.0, 10.0, 5.0);
+        let direction: Vec3 = Vec3::new(0.0, 0.0, -1.0);
+        assert!(instance
+            .hit(
+                &Ray::with_time(origin, direction, 0.0),
+                0.001,
+                f32::INFINITY,
+                &mut Rng::new(0)
+            )
+            .is_none());
+        let rec: HitRecord = instance
+            .hit(
+                &Ray::with_time(origin, direction, 1.0),
+                0.001,
+                f32::INFINITY,
+                &mut Rng::new(0),
+            )
+            .unwrap();
+        assert_close(rec.p, Point3::new(0.0, 10.0, 1.0));
+    }
+}
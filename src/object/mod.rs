@@ -1,28 +1,35 @@
 use crate::material::*;
 use crate::ray::*;
+use crate::rtweekend::*;
 use crate::vec3::*;
-use std::cell::RefCell;
-use std::rc::Rc;
 
-#[derive(Clone)]
-pub struct HitRecord {
+pub struct HitRecord<'a> {
     pub p: Point3,
     pub normal: Vec3,
     pub t: f32,
     pub front_face: bool,
-    // pub mat_ptr: Option<Rc<dyn Material>>,
-    pub mat_ptr: Rc<RefCell<dyn Material>>,
+    pub mat_ptr: &'a dyn Material,
+    /// Surface texture coordinates at `p`.
+    pub u: f32,
+    pub v: f32,
+    /// Weights of the primitive's vertices at `p`; only triangles set these.
+    pub barycentric: [f32; 3],
 }
 
-impl HitRecord {
-    pub fn default() -> Self {
-        Self {
-            p: Point3::new(0.0, 0.0, 0.0),
-            normal: Vec3::new(0.0, 0.0, 0.0),
-            t: 0.0,
-            front_face: false,
-            mat_ptr: Rc::new(RefCell::new(MaterialMock)),
-        }
+impl<'a> HitRecord<'a> {
+    pub fn new(r: &Ray, t: f32, outward_normal: &Vec3, mat_ptr: &'a dyn Material) -> Self {
+        let mut rec = Self {
+            p: r.at(t),
+            normal: *outward_normal,
+            t,
+            front_face: true,
+            mat_ptr,
+            u: 0.0,
+            v: 0.0,
+            barycentric: [0.0; 3],
+        };
+        rec.set_face_normal(r, outward_normal);
+        rec
     }
 
     pub fn set_face_normal(&mut self, r: &Ray, outward_normal: &Vec3) {
@@ -34,10 +41,36 @@ impl HitRecord {
     }
 }
 
+pub mod aabb;
+pub mod box_shape;
+pub mod bvh;
+pub mod constant_medium;
+pub mod density_grid;
+pub mod heterogeneous_medium;
 pub mod hittable;
 pub mod hittable_list;
+pub mod instance;
+pub mod keyframed;
+pub mod moving_sphere;
+pub mod plane;
+pub mod quad;
 pub mod sphere;
+pub mod triangle;
+pub mod triangle_mesh;
 
+pub use aabb::Aabb;
+pub use box_shape::BoxShape;
+pub use bvh::{build_bvh, BvhNode};
+pub use constant_medium::ConstantMedium;
+pub use density_grid::DensityGrid;
+pub use heterogeneous_medium::HeterogeneousMedium;
 pub use hittable::Hittable;
 pub use hittable_list::HittableList;
+pub use instance::Instance;
+pub use keyframed::{Keyframe, KeyframedInstance};
+pub use moving_sphere::MovingSphere;
+pub use plane::Plane;
+pub use quad::Quad;
 pub use sphere::Sphere;
+pub use triangle::Triangle;
+pub use triangle_mesh::TriangleMesh;
@@ -0,0 +1,114 @@
+use super::sphere::hit_sphere;
+use super::*;
+use std::sync::Arc;
+
+/// A sphere moving in a straight line from `center0` at `time0` to `center1`
+/// at `time1`. It rests at either end outside that interval, so its bounding
+/// box covers every time a ray may carry.
+pub struct MovingSphere {
+    center0: Point3,
+    center1: Point3,
+    time0: f32,
+    time1: f32,
+    radius: f32,
+    mat_ptr: Arc<dyn Material>,
+}
+
+impl MovingSphere {
+    pub fn new(
+        center0: Point3,
+        center1: Point3,
+        time0: f32,
+        time1: f32,
+        radius: f32,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            center0,
+            center1,
+            time0,
+            time1,
+            radius,
+            mat_ptr,
+        }
+    }
+
+    pub fn center(&self, time: f32) -> Point3 {
+        if self.time1 <= self.time0 {
+            return self.center0;
+        }
+        let s: f32 = ((time - self.time0) / (self.time1 - self.time0)).clamp(0.0, 1.0);
+        self.center0 + s * (self.center1 - self.center0)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, _rng: &mut Rng) -> Option<HitRecord<'_>> {
+        // Hit the sphere where it stands at the ray's time.
+        hit_sphere(
+            self.center(r.time()),
+            self.radius,
+            self.mat_ptr.as_ref(),
+            r,
+            t_min,
+            t_max,
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r: Vec3 = Vec3::new(self.radius, self.radius, self.radius);
+        Some(Aabb::surrounding_box(
+            &Aabb::new(self.center0 - r, self.center0 + r),
+            &Aabb::new(self.center1 - r, self.center1 + r),
+        ))
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::INFINITY;
+
+    #[test]
+    fn test_position_follows_ray_time() {
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let sphere: MovingSphere = MovingSphere::new(
+            Point3::new(0.0, 0.0, -5.0),
+            Point3::new(0.0, 2.0, -5.0),
+            0.0,
+            1.0,
+            0.5,
+            mat,
+        );
+        assert_eq!(sphere.center(0.5), Point3::new(0.0, 1.0, -5.0));
+        assert_eq!(sphere.center(3.0), Point3::new(0.0, 2.0, -5.0));
+
+        let origin: Point3 = Point3::new(0.0, 2.0, 0.0);
+        let direction: Vec3 = Vec3::new(0.0, 0.0, -1.0);
+        assert!(sphere
+            .hit(
+                &Ray::with_time(origin, direction, 0.0),
+                0.001,
+                INFINITY,
+                &mut Rng::new(0)
+            )
+            .is_none());
+        let rec: HitRecord = sphere
+            .hit(
+                &Ray::with_time(origin, direction, 1.0),
+                0.001,
+                INFINITY,
+                &mut Rng::new(0),
+            )
+            .unwrap();
+        assert_eq!(rec.t, 4.5);
+
+        let bbox: Aabb = sphere.bounding_box().unwrap();
+        assert_eq!(bbox.min(), Point3::new(-0.5, -0.5, -5.5));
+        assert_eq!(bbox.max(), Point3::new(0.5, 2.5, -4.5));
+    }
+}
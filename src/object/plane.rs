@@ -0,0 +1,60 @@
+use super::*;
+use std::sync::Arc;
+
+/// An infinite plane through `point`. It has no bounding box, so it cannot go
+/// in a BVH; `build_bvh` keeps such objects beside the tree.
+pub struct Plane {
+    point: Point3,
+    normal: Vec3,
+    tangent: Vec3,
+    bitangent: Vec3,
+    mat_ptr: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, mat_ptr: Arc<dyn Material>) -> Self {
+        assert!(
+            normal.length_squared() > 0.0,
+            "Plane normal must not be zero."
+        );
+        let normal: Vec3 = normal.unit_vector();
+        let helper: Vec3 = if normal.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let bitangent: Vec3 = normal.cross(&helper).unit_vector();
+        Self {
+            point,
+            normal,
+            tangent: bitangent.cross(&normal),
+            bitangent,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, _rng: &mut Rng) -> Option<HitRecord<'_>> {
+        let denom: f32 = self.normal.dot(&r.direction());
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+        let t: f32 = (self.point - r.origin()).dot(&self.normal) / denom;
+        if t <= t_min || t >= t_max {
+            return None;
+        }
+
+        let mut rec: HitRecord = HitRecord::new(r, t, &self.normal, self.mat_ptr.as_ref());
+        // World-space distances along the plane, so an image texture with
+        // `WrapMode::Repeat` tiles once per unit.
+        let offset: Vec3 = rec.p - self.point;
+        rec.u = offset.dot(&self.tangent);
+        rec.v = offset.dot(&self.bitangent);
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
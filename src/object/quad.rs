@@ -0,0 +1,189 @@
+use super::*;
+use std::sync::Arc;
+
+/// A parallelogram with corner `q` and edges `u` and `v`. Its normal is
+/// `u x v` and its texture coordinates run from 0 to 1 along each edge.
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    /// `n / (n . n)` for the unnormalized normal `n`, which turns a point's
+    /// offset from `q` into its coordinates along `u` and `v`.
+    w: Vec3,
+    normal: Vec3,
+    d: f32,
+    area: f32,
+    mat_ptr: Arc<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, mat_ptr: Arc<dyn Material>) -> Self {
+        let n: Vec3 = u.cross(&v);
+        assert!(n.length_squared() > 0.0, "Quad edges must not be parallel.");
+        let normal: Vec3 = n.unit_vector();
+        Self {
+            q,
+            u,
+            v,
+            w: n / n.dot(&n),
+            normal,
+            d: normal.dot(&q),
+            area: n.length(),
+            mat_ptr,
+        }
+    }
+
+    /// The rectangle `x0..x1` by `y0..y1` in the plane `z = k`, facing +z.
+    pub fn xy_rect(x0: f32, x1: f32, y0: f32, y1: f32, k: f32, mat_ptr: Arc<dyn Material>) -> Self {
+        Quad::new(
+            Point3::new(x0, y0, k),
+            Vec3::new(x1 - x0, 0.0, 0.0),
+            Vec3::new(0.0, y1 - y0, 0.0),
+            mat_ptr,
+        )
+    }
+
+    /// The rectangle `x0..x1` by `z0..z1` in the plane `y = k`, facing +y. Its
+    /// u coordinate runs along z and v along x.
+    pub fn xz_rect(x0: f32, x1: f32, z0: f32, z1: f32, k: f32, mat_ptr: Arc<dyn Material>) -> Self {
+        Quad::new(
+            Point3::new(x0, k, z0),
+            Vec3::new(0.0, 0.0, z1 - z0),
+            Vec3::new(x1 - x0, 0.0, 0.0),
+            mat_ptr,
+        )
+    }
+
+    /// The rectangle `y0..y1` by `z0..z1` in the plane `x = k`, facing +x.
+    pub fn yz_rect(y0: f32, y1: f32, z0: f32, z1: f32, k: f32, mat_ptr: Arc<dyn Material>) -> Self {
+        Quad::new(
+            Point3::new(k, y0, z0),
+            Vec3::new(0.0, y1 - y0, 0.0),
+            Vec3::new(0.0, 0.0, z1 - z0),
+            mat_ptr,
+        )
+    }
+}
+
+impl Quad {
+    /// The intersection itself, which needs no random numbers, so that
+    /// `pdf_value` can look for it too.
+    fn intersect(&self, r: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let denom: f32 = self.normal.dot(&r.direction());
+        // Rays parallel to the plane never hit it.
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+        let t: f32 = (self.d - self.normal.dot(&r.origin())) / denom;
+        if t <= t_min || t >= t_max {
+            return None;
+        }
+
+        let planar: Vec3 = r.at(t) - self.q;
+        let alpha: f32 = self.w.dot(&planar.cross(&self.v));
+        let beta: f32 = self.w.dot(&self.u.cross(&planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let mut rec: HitRecord = HitRecord::new(r, t, &self.normal, self.mat_ptr.as_ref());
+        rec.u = alpha;
+        rec.v = beta;
+        Some(rec)
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, _rng: &mut Rng) -> Option<HitRecord<'_>> {
+        self.intersect(r, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let diagonal1: Aabb = Aabb::new(self.q, self.q + self.u + self.v);
+        let diagonal2: Aabb = Aabb::new(self.q + self.u, self.q + self.v);
+        Some(Aabb::surrounding_box(&diagonal1, &diagonal2).pad(1e-4))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        match self.intersect(&Ray::new(*origin, *direction), 0.001, INFINITY) {
+            Some(rec) => {
+                // Points spread uniformly over the area subtend a solid angle that
+                // shrinks with the squared distance and the obliqueness of the view.
+                let distance_squared: f32 = rec.t * rec.t * direction.length_squared();
+                let cosine: f32 = (direction.dot(&rec.normal) / direction.length()).abs();
+                distance_squared / (cosine * self.area)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        self.q + u.0 * self.u + u.1 * self.v - *origin
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::INFINITY;
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn test_hit_uv_and_normal() {
+        let quad: Quad = Quad::new(
+            Point3::new(-1.0, -1.0, 0.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            material(),
+        );
+        let r: Ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec: HitRecord = quad.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+        assert_eq!(rec.t, 5.0);
+        assert_eq!((rec.u, rec.v), (0.25, 0.5));
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert!(rec.front_face);
+
+        let outside: Ray = Ray::new(Point3::new(3.5, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(quad
+            .hit(&outside, 0.001, INFINITY, &mut Rng::new(0))
+            .is_none());
+        let parallel: Ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(quad
+            .hit(&parallel, 0.001, INFINITY, &mut Rng::new(0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_rects_face_positive_axes() {
+        let rects: [(Quad, Vec3); 3] = [
+            (
+                Quad::xy_rect(0.0, 1.0, 0.0, 1.0, 2.0, material()),
+                Vec3::new(0.0, 0.0, 1.0),
+            ),
+            (
+                Quad::xz_rect(0.0, 1.0, 0.0, 1.0, 2.0, material()),
+                Vec3::new(0.0, 1.0, 0.0),
+            ),
+            (
+                Quad::yz_rect(0.0, 1.0, 0.0, 1.0, 2.0, material()),
+                Vec3::new(1.0, 0.0, 0.0),
+            ),
+        ];
+        for (rect, axis) in rects.iter() {
+            // Fire at the rect's center from the positive side.
+            let center: Point3 = Point3::new(0.5, 0.5, 0.5) + 1.5 * *axis;
+            let r: Ray = Ray::new(center + 3.0 * *axis, -*axis);
+            let rec: HitRecord = rect.hit(&r, 0.001, INFINITY, &mut Rng::new(0)).unwrap();
+            assert!(rec.front_face);
+            assert_eq!(rec.normal, *axis);
+            assert_eq!((rec.u, rec.v), (0.5, 0.5));
+        }
+    }
+}
@@ -1,55 +1,154 @@
 use super::*;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct Sphere {
     center: Point3,
     radius: f32,
-    mat_ptr: Rc<RefCell<dyn Material>>,
+    mat_ptr: Arc<dyn Material>,
 }
 
 impl Sphere {
-    pub fn new(center: Point3, radius: f32, mat_ptr: Rc<RefCell<dyn Material>>) -> Self {
+    pub fn new(center: Point3, radius: f32, mat_ptr: Arc<dyn Material>) -> Self {
         Self {
             center,
             radius,
             mat_ptr,
         }
     }
+
+    /// Maps a point on the unit sphere to (u, v): u runs around the y axis
+    /// starting from -x, v from the south pole (0) to the north pole (1).
+    pub fn uv(p: &Point3) -> (f32, f32) {
+        let theta: f32 = (-p.y()).clamp(-1.0, 1.0).acos();
+        let phi: f32 = (-p.z()).atan2(p.x()) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
+}
+
+/// Intersects the sphere at `center` with radius `radius`, filling in its
+/// outward normal and spherical texture coordinates.
+pub(crate) fn hit_sphere<'a>(
+    center: Point3,
+    radius: f32,
+    mat_ptr: &'a dyn Material,
+    r: &Ray,
+    t_min: f32,
+    t_max: f32,
+) -> Option<HitRecord<'a>> {
+    let oc: Vec3 = r.origin() - center;
+    let a: f32 = r.direction().length_squared();
+    let half_b: f32 = oc.dot(&r.direction());
+    let c: f32 = oc.length_squared() - radius * radius;
+    let discriminant: f32 = half_b * half_b - a * c;
+    if discriminant <= 0f32 {
+        return None;
+    }
+
+    let root: f32 = discriminant.sqrt();
+    let mut temp: f32 = (-half_b - root) / a;
+    if temp >= t_max || temp <= t_min {
+        temp = (-half_b + root) / a;
+        if temp >= t_max || temp <= t_min {
+            return None;
+        }
+    }
+
+    let outward_normal: Vec3 = (r.at(temp) - center) / radius;
+    let mut rec: HitRecord = HitRecord::new(r, temp, &outward_normal, mat_ptr);
+    (rec.u, rec.v) = Sphere::uv(&outward_normal);
+    Some(rec)
 }
 
 impl Hittable for Sphere {
-    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, rec: &mut HitRecord) -> bool {
-        let oc: Vec3 = r.origin() - self.center;
-        let a: f32 = r.direction().length_squared();
-        let half_b: f32 = oc.dot(&r.direction());
-        let c: f32 = oc.length_squared() - self.radius * self.radius;
-        let discriminant: f32 = half_b * half_b - a * c;
-
-        if discriminant > 0f32 {
-            let root: f32 = discriminant.sqrt();
-
-            let temp: f32 = (-half_b - root) / a;
-            if temp < t_max && temp > t_min {
-                rec.t = temp;
-                rec.p = r.at(rec.t);
-                let outward_normal: Vec3 = (rec.p - self.center) / self.radius;
-                rec.set_face_normal(r, &outward_normal);
-                rec.mat_ptr = self.mat_ptr.clone();
-                return true;
-            }
-
-            let temp = (-half_b + root) / a;
-            if temp < t_max && temp > t_min {
-                rec.t = temp;
-                rec.p = r.at(rec.t);
-                rec.normal = (rec.p - self.center) / self.radius;
-                let outward_normal: Vec3 = (rec.p - self.center) / self.radius;
-                rec.set_face_normal(r, &outward_normal);
-                rec.mat_ptr = self.mat_ptr.clone();
-                return true;
-            }
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, _rng: &mut Rng) -> Option<HitRecord<'_>> {
+        hit_sphere(
+            self.center,
+            self.radius,
+            self.mat_ptr.as_ref(),
+            r,
+            t_min,
+            t_max,
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r: Vec3 = Vec3::new(self.radius, self.radius, self.radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let distance_squared: f32 = (self.center - *origin).length_squared();
+        if distance_squared <= self.radius * self.radius {
+            // From inside, every direction reaches the sphere.
+            return 1.0 / (4.0 * PI);
+        }
+        let r: Ray = Ray::new(*origin, *direction);
+        if hit_sphere(
+            self.center,
+            self.radius,
+            self.mat_ptr.as_ref(),
+            &r,
+            0.001,
+            INFINITY,
+        )
+        .is_none()
+        {
+            return 0.0;
         }
-        false
+        let cos_theta_max: f32 = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    /// Samples the cone of directions the sphere fills, seen from `origin`.
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        let direction: Vec3 = self.center - *origin;
+        let distance_squared: f32 = direction.length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return Vec3::sample_unit_vector(u);
+        }
+        let cos_theta_max: f32 = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        Onb::build_from_w(&direction).local(&Vec3::sample_in_cone(cos_theta_max, u))
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_uv(p: Point3, expected: (f32, f32)) {
+        let (u, v) = Sphere::uv(&p);
+        assert!(
+            (u - expected.0).abs() < 1e-6 && (v - expected.1).abs() < 1e-6,
+            "{:?} -> ({}, {})",
+            p,
+            u,
+            v
+        );
+    }
+
+    #[test]
+    fn test_uv() {
+        assert_uv(Point3::new(1.0, 0.0, 0.0), (0.5, 0.5));
+        assert_uv(Point3::new(0.0, 1.0, 0.0), (0.5, 1.0));
+        assert_uv(Point3::new(0.0, -1.0, 0.0), (0.5, 0.0));
+        assert_uv(Point3::new(-1.0, 0.0, 0.0), (0.0, 0.5));
+        assert_uv(Point3::new(0.0, 0.0, 1.0), (0.25, 0.5));
+        assert_uv(Point3::new(0.0, 0.0, -1.0), (0.75, 0.5));
+    }
+
+    #[test]
+    fn test_hit_records_uv() {
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let sphere: Sphere = Sphere::new(Point3::new(0.0, 0.0, -5.0), 2.0, mat);
+        let r: Ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec: HitRecord = sphere
+            .hit(&r, 0.0, f32::INFINITY, &mut Rng::new(0))
+            .unwrap();
+        assert_eq!(rec.t, 3.0);
+        assert!((rec.u - 0.25).abs() < 1e-6 && (rec.v - 0.5).abs() < 1e-6);
     }
 }
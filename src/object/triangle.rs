@@ -0,0 +1,176 @@
+use super::*;
+use std::sync::Arc;
+
+const EPSILON: f32 = 1e-8;
+
+pub struct Triangle {
+    mesh: Arc<TriangleMesh>,
+    face: usize,
+}
+
+impl Triangle {
+    pub fn new(mesh: Arc<TriangleMesh>, face: usize) -> Self {
+        assert!(face < mesh.len(), "Triangle face out of range.");
+        Self { mesh, face }
+    }
+}
+
+impl Hittable for Triangle {
+    /// Möller–Trumbore ray/triangle intersection.
+    fn hit(&self, r: &Ray, t_min: f32, t_max: f32, _rng: &mut Rng) -> Option<HitRecord<'_>> {
+        let [i0, i1, i2] = self.mesh.face(self.face);
+        let p0: Point3 = self.mesh.position(i0);
+        let edge1: Vec3 = self.mesh.position(i1) - p0;
+        let edge2: Vec3 = self.mesh.position(i2) - p0;
+
+        let pvec: Vec3 = r.direction().cross(&edge2);
+        let det: f32 = edge1.dot(&pvec);
+        if det.abs() < EPSILON {
+            return None;
+        }
+        let inv_det: f32 = 1.0 / det;
+
+        let tvec: Vec3 = r.origin() - p0;
+        let b1: f32 = tvec.dot(&pvec) * inv_det;
+        if !(0.0..=1.0).contains(&b1) {
+            return None;
+        }
+
+        let qvec: Vec3 = tvec.cross(&edge1);
+        let b2: f32 = r.direction().dot(&qvec) * inv_det;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return None;
+        }
+
+        let t: f32 = edge2.dot(&qvec) * inv_det;
+        if t >= t_max || t <= t_min {
+            return None;
+        }
+        let b0: f32 = 1.0 - b1 - b2;
+
+        let interpolated_normal: Option<Vec3> = match (
+            self.mesh.normal(i0),
+            self.mesh.normal(i1),
+            self.mesh.normal(i2),
+        ) {
+            (Some(n0), Some(n1), Some(n2)) => Some((b0 * n0 + b1 * n1 + b2 * n2).unit_vector()),
+            _ => None,
+        };
+
+        // The geometric normal decides which side was hit; when the mesh has vertex
+        // normals it is flipped to agree with them, whatever the winding order.
+        let mut outward_normal: Vec3 = edge1.cross(&edge2).unit_vector();
+        if let Some(n) = interpolated_normal {
+            if n.dot(&outward_normal) < 0.0 {
+                outward_normal = -outward_normal;
+            }
+        }
+
+        let mut rec: HitRecord = HitRecord::new(r, t, &outward_normal, self.mesh.material());
+        if let Some(n) = interpolated_normal {
+            rec.normal = if rec.front_face { n } else { -n };
+        }
+        let (u, v) = match (self.mesh.uv(i0), self.mesh.uv(i1), self.mesh.uv(i2)) {
+            (Some(uv0), Some(uv1), Some(uv2)) => (
+                b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+                b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+            ),
+            _ => (b1, b2),
+        };
+        rec.u = u;
+        rec.v = v;
+        rec.barycentric = [b0, b1, b2];
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let [i0, i1, i2] = self.mesh.face(self.face);
+        let b: Aabb = Aabb::new(self.mesh.position(i0), self.mesh.position(i1));
+        let p2: Point3 = self.mesh.position(i2);
+        Some(Aabb::surrounding_box(&b, &Aabb::new(p2, p2)).pad(1e-4))
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_triangle() -> TriangleMesh {
+        TriangleMesh::new(
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2]],
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn test_hit_barycentric() {
+        let tri: Triangle = Triangle::new(Arc::new(unit_triangle()), 0);
+        let r: Ray = Ray::new(Point3::new(0.25, 0.5, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec: HitRecord = tri.hit(&r, 0.001, f32::INFINITY, &mut Rng::new(0)).unwrap();
+        assert_eq!(rec.t, 1.0);
+        assert_eq!(rec.p, Point3::new(0.25, 0.5, 0.0));
+        assert_eq!(rec.barycentric, [0.25, 0.25, 0.5]);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert!(rec.front_face);
+    }
+
+    #[test]
+    fn test_miss() {
+        let tri: Triangle = Triangle::new(Arc::new(unit_triangle()), 0);
+        let r: Ray = Ray::new(Point3::new(0.75, 0.75, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(tri
+            .hit(&r, 0.001, f32::INFINITY, &mut Rng::new(0))
+            .is_none());
+        let r: Ray = Ray::new(Point3::new(0.25, 0.25, 1.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(tri
+            .hit(&r, 0.001, f32::INFINITY, &mut Rng::new(0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_back_face_and_vertex_attributes() {
+        let mesh: TriangleMesh = unit_triangle()
+            .with_normals(vec![Vec3::new(0.0, 0.0, 1.0); 3])
+            .with_uvs(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        let tri: Triangle = Triangle::new(Arc::new(mesh), 0);
+        let r: Ray = Ray::new(Point3::new(0.5, 0.25, -1.0), Vec3::new(0.0, 0.0, 1.0));
+        let rec: HitRecord = tri.hit(&r, 0.001, f32::INFINITY, &mut Rng::new(0)).unwrap();
+        assert!(!rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!((rec.u, rec.v), (0.5, 0.25));
+    }
+
+    #[test]
+    fn test_mesh_shares_vertices() {
+        let mesh: TriangleMesh = TriangleMesh::new(
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let quad: BvhNode = BvhNode::new(mesh.into_triangles());
+        let bbox: Aabb = quad.bounding_box().unwrap();
+        assert_eq!(bbox.min().x(), 0.0);
+        assert_eq!(bbox.max().y(), 1.0);
+        assert!(bbox.max().z() > bbox.min().z());
+        for (x, y) in [(0.9, 0.1), (0.1, 0.9)].iter() {
+            let r: Ray = Ray::new(Point3::new(*x, *y, 1.0), Vec3::new(0.0, 0.0, -1.0));
+            assert!(quad
+                .hit(&r, 0.001, f32::INFINITY, &mut Rng::new(0))
+                .is_some());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use super::*;
+use std::sync::Arc;
+
+/// Vertex data shared by every triangle of a mesh. Each face stores three
+/// indices into the per-vertex arrays rather than its own copy of the vertices.
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Option<Vec<Vec3>>,
+    uvs: Option<Vec<(f32, f32)>>,
+    indices: Vec<[usize; 3]>,
+    mat_ptr: Arc<dyn Material>,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<Point3>,
+        indices: Vec<[usize; 3]>,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        assert!(
+            indices.iter().flatten().all(|&i| i < positions.len()),
+            "TriangleMesh index out of range."
+        );
+        Self {
+            positions,
+            normals: None,
+            uvs: None,
+            indices,
+            mat_ptr,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: Vec<Vec3>) -> Self {
+        assert_eq!(
+            normals.len(),
+            self.positions.len(),
+            "One normal per vertex."
+        );
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: Vec<(f32, f32)>) -> Self {
+        assert_eq!(uvs.len(), self.positions.len(), "One uv per vertex.");
+        self.uvs = Some(uvs);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn face(&self, index: usize) -> [usize; 3] {
+        self.indices[index]
+    }
+
+    pub fn position(&self, vertex: usize) -> Point3 {
+        self.positions[vertex]
+    }
+
+    pub fn normal(&self, vertex: usize) -> Option<Vec3> {
+        self.normals.as_ref().map(|n| n[vertex])
+    }
+
+    pub fn uv(&self, vertex: usize) -> Option<(f32, f32)> {
+        self.uvs.as_ref().map(|uv| uv[vertex])
+    }
+
+    pub fn material(&self) -> &dyn Material {
+        self.mat_ptr.as_ref()
+    }
+
+    /// Creates one `Triangle` per face, all pointing back at this mesh.
+    pub fn into_triangles(self) -> HittableList {
+        let mesh: Arc<TriangleMesh> = Arc::new(self);
+        let mut list: HittableList = HittableList::new();
+        for face in 0..mesh.len() {
+            list.add(Arc::new(Triangle::new(mesh.clone(), face)));
+        }
+        list
+    }
+}
@@ -0,0 +1,25 @@
+use super::*;
+
+/// Cosine-weighted directions about a surface normal, the distribution of
+/// light leaving a Lambertian surface.
+pub struct CosinePdf {
+    uvw: Onb,
+}
+
+impl CosinePdf {
+    pub fn new(normal: &Vec3) -> Self {
+        Self {
+            uvw: Onb::build_from_w(normal),
+        }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: &Vec3) -> f32 {
+        (direction.unit_vector().dot(&self.uvw.w()) / PI).max(0.0)
+    }
+
+    fn generate(&self, u: (f32, f32)) -> Vec3 {
+        self.uvw.local(&Vec3::sample_cosine_direction(u))
+    }
+}
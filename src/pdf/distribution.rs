@@ -0,0 +1,173 @@
+/// A piecewise-constant density over [0, 1) with one equally wide bin per
+/// weight, sampled by inverting its cumulative distribution. If every weight
+/// is zero the bins are picked uniformly instead.
+#[derive(Clone, Debug)]
+pub struct Distribution1D {
+    /// Density of each bin; they average to 1.
+    density: Vec<f32>,
+    /// Probability of landing in bins `0..=i`.
+    cdf: Vec<f32>,
+    total: f32,
+}
+
+impl Distribution1D {
+    pub fn new(weights: &[f32]) -> Self {
+        assert!(
+            !weights.is_empty(),
+            "Distribution1D needs at least one bin."
+        );
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "Distribution1D weights must be finite and non-negative."
+        );
+        let total: f32 = weights.iter().sum();
+        let n: f32 = weights.len() as f32;
+        let density: Vec<f32> = if total > 0.0 {
+            weights.iter().map(|w| w * n / total).collect()
+        } else {
+            vec![1.0; weights.len()]
+        };
+        let mut sum: f32 = 0.0;
+        let mut cdf: Vec<f32> = density
+            .iter()
+            .map(|d| {
+                sum += d / n;
+                sum
+            })
+            .collect();
+        // Rounding must not leave a sliver past the last bin.
+        *cdf.last_mut().unwrap() = 1.0;
+        Self {
+            density,
+            cdf,
+            total,
+        }
+    }
+
+    /// The sum of the weights the distribution was built from.
+    pub fn total(&self) -> f32 {
+        self.total
+    }
+
+    pub fn len(&self) -> usize {
+        self.density.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.density.is_empty()
+    }
+
+    /// Maps `u` in [0, 1) to a point `x` in [0, 1) distributed by the weights,
+    /// returning `x`, its bin and its density.
+    pub fn sample(&self, u: f32) -> (f32, usize, f32) {
+        let bin: usize = self.cdf.partition_point(|&c| c <= u).min(self.len() - 1);
+        let start: f32 = if bin == 0 { 0.0 } else { self.cdf[bin - 1] };
+        let width: f32 = self.cdf[bin] - start;
+        let offset: f32 = if width > 0.0 {
+            ((u - start) / width).clamp(0.0, 1.0 - f32::EPSILON)
+        } else {
+            0.0
+        };
+        (
+            (bin as f32 + offset) / self.len() as f32,
+            bin,
+            self.density[bin],
+        )
+    }
+
+    /// The density of the bin containing `x`.
+    pub fn value(&self, x: f32) -> f32 {
+        self.density[self.bin(x)]
+    }
+
+    fn bin(&self, x: f32) -> usize {
+        ((x * self.len() as f32).max(0.0) as usize).min(self.len() - 1)
+    }
+}
+
+/// A piecewise-constant density over the unit square, one bin per pixel of a
+/// `width` x `height` grid of weights stored row by row. Rows are picked by
+/// their total weight, then a column within the row.
+#[derive(Clone, Debug)]
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(weights: &[f32], width: usize, height: usize) -> Self {
+        assert_eq!(
+            weights.len(),
+            width * height,
+            "Distribution2D needs one weight per bin."
+        );
+        let rows: Vec<Distribution1D> = weights
+            .chunks_exact(width)
+            .map(Distribution1D::new)
+            .collect();
+        let totals: Vec<f32> = rows.iter().map(|row| row.total()).collect();
+        Self {
+            rows,
+            marginal: Distribution1D::new(&totals),
+        }
+    }
+
+    /// Maps `(u, v)` in the unit square to a point `(x, y)` distributed by the
+    /// weights, with `y` picking the row, and returns it with its density.
+    pub fn sample(&self, u: f32, v: f32) -> (f32, f32, f32) {
+        let (y, row, row_density) = self.marginal.sample(v);
+        let (x, _, density) = self.rows[row].sample(u);
+        (x, y, row_density * density)
+    }
+
+    /// The density with which `sample` returns points in the bin of `(x, y)`.
+    pub fn value(&self, x: f32, y: f32) -> f32 {
+        self.marginal.value(y) * self.rows[self.marginal.bin(y)].value(x)
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtweekend::Rng;
+
+    #[test]
+    fn test_sample_1d() {
+        let distribution: Distribution1D = Distribution1D::new(&[1.0, 3.0, 0.0, 4.0]);
+        assert_eq!(distribution.total(), 8.0);
+        assert_eq!(distribution.sample(0.0), (0.0, 0, 0.5));
+        // Halfway through the second bin's share of the cdf.
+        assert_eq!(distribution.sample(0.125 + 0.1875), (0.375, 1, 1.5));
+        // The empty bin is never picked.
+        let (x, bin, density) = distribution.sample(0.5);
+        assert_eq!((x, bin, density), (0.75, 3, 2.0));
+        assert_eq!(distribution.value(0.6), 0.0);
+        assert!(distribution.sample(0.999_999).0 < 1.0);
+
+        let uniform: Distribution1D = Distribution1D::new(&[0.0, 0.0]);
+        assert_eq!(uniform.sample(0.75), (0.75, 1, 1.0));
+    }
+
+    #[test]
+    fn test_sample_2d_follows_weights() {
+        // A 2x2 grid with all its weight in the bottom row, mostly on the right.
+        let distribution: Distribution2D = Distribution2D::new(&[0.0, 0.0, 1.0, 3.0], 2, 2);
+        let mut rng: Rng = Rng::new(7);
+        let trials: usize = 20000;
+        let mut right: usize = 0;
+        for _ in 0..trials {
+            let (x, y, density) = distribution.sample(rng.random_double(), rng.random_double());
+            assert!(y >= 0.5);
+            assert_eq!(density, distribution.value(x, y));
+            if x >= 0.5 {
+                right += 1;
+                assert_eq!(density, 3.0);
+            }
+        }
+        assert!((right as f32 / trials as f32 - 0.75).abs() < 0.02);
+    }
+}
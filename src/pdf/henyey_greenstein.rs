@@ -0,0 +1,83 @@
+use super::*;
+
+/// Directions scattered by the Henyey–Greenstein phase function around the
+/// direction of travel. The asymmetry `g` in (-1, 1) is the mean cosine of the
+/// scattering angle: positive values scatter forwards like cloud droplets,
+/// negative ones backwards, and 0 is isotropic.
+pub struct HenyeyGreensteinPdf {
+    uvw: Onb,
+    g: f32,
+}
+
+impl HenyeyGreensteinPdf {
+    pub fn new(direction: &Vec3, g: f32) -> Self {
+        Self {
+            uvw: Onb::build_from_w(direction),
+            g,
+        }
+    }
+
+    /// Density over the sphere of scattering by an angle with cosine `cos_theta`.
+    pub fn phase(g: f32, cos_theta: f32) -> f32 {
+        let denom: f32 = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * PI * denom * denom.sqrt())
+    }
+
+    /// Inverts the cumulative distribution of the phase function.
+    fn sample_cos_theta(g: f32, xi: f32) -> f32 {
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * xi;
+        }
+        let s: f32 = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Pdf for HenyeyGreensteinPdf {
+    fn value(&self, direction: &Vec3) -> f32 {
+        HenyeyGreensteinPdf::phase(self.g, direction.unit_vector().dot(&self.uvw.w()))
+    }
+
+    fn generate(&self, u: (f32, f32)) -> Vec3 {
+        let cos_theta: f32 = HenyeyGreensteinPdf::sample_cos_theta(self.g, u.0);
+        let sin_theta: f32 = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi: f32 = 2.0 * PI * u.1;
+        self.uvw.local(&Vec3::new(
+            sin_theta * phi.cos(),
+            sin_theta * phi.sin(),
+            cos_theta,
+        ))
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_integrates_to_one() {
+        for g in [-0.7, 0.0, 0.3, 0.9] {
+            let steps: usize = 20000;
+            let mut total: f32 = 0.0;
+            for i in 0..steps {
+                let cos_theta: f32 = -1.0 + 2.0 * (i as f32 + 0.5) / steps as f32;
+                total += HenyeyGreensteinPdf::phase(g, cos_theta) * 2.0 * PI * 2.0 / steps as f32;
+            }
+            assert!((total - 1.0).abs() < 1e-2, "g = {}: {}", g, total);
+        }
+    }
+
+    #[test]
+    fn test_sampled_mean_cosine_is_g() {
+        let n: usize = 20000;
+        let mean: f32 = (0..n)
+            .map(|i| HenyeyGreensteinPdf::sample_cos_theta(0.6, (i as f32 + 0.5) / n as f32))
+            .sum::<f32>()
+            / n as f32;
+        assert!((mean - 0.6).abs() < 1e-2, "{}", mean);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::rtweekend::*;
+use crate::vec3::*;
+
+/// A probability density over directions, used to importance sample where a
+/// path goes next.
+pub trait Pdf {
+    /// Solid-angle density with which `generate` returns `direction`.
+    fn value(&self, direction: &Vec3) -> f32;
+
+    /// The direction that `u`, a point of the unit square, maps to, so that
+    /// uniform `u` gives directions distributed according to `value`. It
+    /// need not be normalized.
+    fn generate(&self, u: (f32, f32)) -> Vec3;
+}
+
+pub mod cosine;
+pub mod distribution;
+pub mod henyey_greenstein;
+pub mod phong;
+pub mod sphere;
+
+pub use cosine::CosinePdf;
+pub use distribution::{Distribution1D, Distribution2D};
+pub use henyey_greenstein::HenyeyGreensteinPdf;
+pub use phong::PhongPdf;
+pub use sphere::SpherePdf;
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::*;
+    use crate::object::*;
+    use std::sync::Arc;
+
+    /// Integrates `density` over the sphere of directions by uniform sampling.
+    fn total_density(density: &dyn Fn(&Vec3) -> f32) -> f32 {
+        let mut rng: Rng = Rng::new(7);
+        let n: usize = 200000;
+        (0..n)
+            .map(|_| density(&Vec3::random_unit_vector(&mut rng)))
+            .sum::<f32>()
+            * 4.0
+            * PI
+            / n as f32
+    }
+
+    fn light() -> Arc<dyn Material> {
+        Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0)))
+    }
+
+    #[test]
+    fn test_densities_integrate_to_one() {
+        let mut lights: HittableList = HittableList::new();
+        lights.add(Arc::new(Quad::xz_rect(-1.0, 1.0, -1.0, 1.0, 2.0, light())));
+        lights.add(Arc::new(Sphere::new(
+            Point3::new(3.0, 0.0, 0.0),
+            1.0,
+            light(),
+        )));
+        let origin: Point3 = Point3::new(0.0, 0.0, 0.0);
+
+        let cosine: CosinePdf = CosinePdf::new(&Vec3::new(0.0, 1.0, 0.0));
+        let phong: PhongPdf = PhongPdf::new(&Vec3::new(1.0, 1.0, 0.0), 20.0);
+        for pdf in [&cosine as &dyn Pdf, &SpherePdf, &phong] {
+            let total: f32 = total_density(&|direction| pdf.value(direction));
+            assert!((total - 1.0).abs() < 0.03, "{}", total);
+        }
+        // Sampling the lights as a whole, as next-event estimation does.
+        let total: f32 = total_density(&|direction| lights.pdf_value(&origin, direction));
+        assert!((total - 1.0).abs() < 0.03, "{}", total);
+    }
+
+    #[test]
+    fn test_generated_directions_have_density() {
+        let quad: Quad = Quad::xy_rect(0.0, 1.0, 0.0, 1.0, -3.0, light());
+        let sphere: Sphere = Sphere::new(Point3::new(0.0, 5.0, 0.0), 0.5, light());
+        let origin: Point3 = Point3::new(0.2, 0.1, 0.0);
+        let mut rng: Rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!(
+                quad.pdf_value(
+                    &origin,
+                    &quad.random(&origin, (rng.random_double(), rng.random_double()))
+                ) > 0.0
+            );
+            assert!(
+                sphere.pdf_value(
+                    &origin,
+                    &sphere.random(&origin, (rng.random_double(), rng.random_double()))
+                ) > 0.0
+            );
+        }
+    }
+}
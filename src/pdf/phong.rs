@@ -0,0 +1,38 @@
+use super::*;
+
+/// Directions clustered around `axis`, with density proportional to the
+/// cosine of the angle to it raised to `exponent`: a glossy reflection lobe.
+pub struct PhongPdf {
+    uvw: Onb,
+    exponent: f32,
+}
+
+impl PhongPdf {
+    pub fn new(axis: &Vec3, exponent: f32) -> Self {
+        Self {
+            uvw: Onb::build_from_w(axis),
+            exponent,
+        }
+    }
+}
+
+impl Pdf for PhongPdf {
+    fn value(&self, direction: &Vec3) -> f32 {
+        let cosine: f32 = direction.unit_vector().dot(&self.uvw.w());
+        if cosine <= 0.0 {
+            return 0.0;
+        }
+        (self.exponent + 1.0) / (2.0 * PI) * cosine.powf(self.exponent)
+    }
+
+    fn generate(&self, u: (f32, f32)) -> Vec3 {
+        let cos_theta: f32 = u.0.powf(1.0 / (self.exponent + 1.0));
+        let sin_theta: f32 = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi: f32 = 2.0 * PI * u.1;
+        self.uvw.local(&Vec3::new(
+            sin_theta * phi.cos(),
+            sin_theta * phi.sin(),
+            cos_theta,
+        ))
+    }
+}
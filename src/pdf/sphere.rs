@@ -0,0 +1,14 @@
+use super::*;
+
+/// Directions spread uniformly over the whole sphere.
+pub struct SpherePdf;
+
+impl Pdf for SpherePdf {
+    fn value(&self, _direction: &Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn generate(&self, u: (f32, f32)) -> Vec3 {
+        Vec3::sample_unit_vector(u)
+    }
+}
@@ -0,0 +1,49 @@
+/// How multiple importance sampling splits the credit for a path between the
+/// light sample and the material sample that could both have produced it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MisHeuristic {
+    /// Weights in proportion to each strategy's density.
+    #[default]
+    Balance,
+    /// Weights in proportion to the squared densities, favouring whichever
+    /// strategy is clearly better for a direction even more.
+    Power,
+}
+
+impl MisHeuristic {
+    /// Weight of a sample drawn with density `pdf` when `other_pdf` is the
+    /// density the other strategy gives the same direction.
+    pub fn weight(&self, pdf: f32, other_pdf: f32) -> f32 {
+        let (a, b) = match self {
+            MisHeuristic::Balance => (pdf, other_pdf),
+            MisHeuristic::Power => (pdf * pdf, other_pdf * other_pdf),
+        };
+        if a + b > 0.0 {
+            a / (a + b)
+        } else {
+            0.0
+        }
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weights_sum_to_one() {
+        for heuristic in [MisHeuristic::Balance, MisHeuristic::Power] {
+            for (a, b) in [(1.0, 3.0), (0.2, 0.0), (5.0, 5.0)] {
+                let total: f32 = heuristic.weight(a, b) + heuristic.weight(b, a);
+                assert!((total - 1.0).abs() < 1e-6);
+            }
+        }
+        assert_eq!(MisHeuristic::Balance.weight(1.0, 3.0), 0.25);
+        assert_eq!(MisHeuristic::Power.weight(1.0, 3.0), 0.1);
+        assert_eq!(MisHeuristic::Power.weight(0.0, 0.0), 0.0);
+    }
+}
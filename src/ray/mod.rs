@@ -1,17 +1,30 @@
+use crate::environment::Environment;
+use crate::material::ScatterKind;
 use crate::object::hittable::Hittable;
-use crate::object::*;
+use crate::object::HittableList;
 use crate::rtweekend::*;
+use crate::sampler::Sampler;
 use crate::vec3::*;
 
 #[derive(Default, Copy, Clone)]
 pub struct Ray {
     origin: Point3,
     direction: Point3,
+    /// When the ray was cast, within the camera's shutter interval.
+    time: f32,
 }
 
 impl Ray {
     pub fn new(origin: Point3, direction: Point3) -> Self {
-        Self { origin, direction }
+        Ray::with_time(origin, direction, 0.0)
+    }
+
+    pub fn with_time(origin: Point3, direction: Point3, time: f32) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+        }
     }
 
     pub fn origin(&self) -> Point3 {
@@ -22,37 +35,176 @@ impl Ray {
         self.direction
     }
 
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
     pub fn at(&self, t: f32) -> Point3 {
         self.origin + t * self.direction
     }
 
-    pub fn ray_color<T: Hittable>(&self, world: &T, depth: u16) -> Color {
-        if depth <= 0 {
-            return Color::new(0.0, 0.0, 0.0);
-        }
-        let mut rec: HitRecord = HitRecord::default();
-        if world.hit(self, 0.001, INFINITY, &mut rec) {
-            let mut scattered: Ray = Ray::default();
-            let mut attenuation: Color = Color::default();
-            if rec
-                .mat_ptr
-                .borrow_mut()
-                .scatter(self, &rec, &mut attenuation, &mut scattered)
-            {
-                return attenuation * scattered.ray_color(world, depth - 1);
+    /// Radiance arriving along the ray: light emitted by the surfaces it
+    /// bounces off plus, for rays that escape, the `environment`. Diffuse
+    /// bounces also send a ray straight towards `lights`, which must be part
+    /// of `world` too, and another towards the environment if it can be
+    /// sampled; `path.heuristic` weighs what these find against what the
+    /// material's own sample finds. With no lights every path follows the
+    /// material.
+    ///
+    /// The path is followed bounce by bounce, tracking the throughput that
+    /// light found further along is scaled by. After `path.min_bounces`,
+    /// Russian roulette ends dim paths early and boosts the survivors to keep
+    /// the estimate unbiased; `path.max_depth` bounces end any path. Every
+    /// random choice is drawn from `sampler`.
+    pub fn ray_color<T: Hittable>(
+        &self,
+        world: &T,
+        lights: &HittableList,
+        environment: &dyn Environment,
+        path: &PathSettings,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let mut color: Color = Color::new(0.0, 0.0, 0.0);
+        let mut throughput: Color = Color::new(1.0, 1.0, 1.0);
+        let mut ray: Ray = *self;
+        // Density the last diffuse bounce sampled `ray` with; `None` after the
+        // camera or a mirror.
+        let mut bsdf_pdf: Option<f32> = None;
+
+        for bounce in 0..path.max_depth {
+            let rec = match world.hit(&ray, 0.001, INFINITY, sampler.rng()) {
+                Some(rec) => rec,
+                None => {
+                    let mut escaped: Color = environment.color(&ray.direction());
+                    if let Some(bsdf_pdf) = bsdf_pdf {
+                        let environment_pdf: f32 = environment.pdf_value(&ray.direction());
+                        escaped *= path.heuristic.weight(bsdf_pdf, environment_pdf);
+                    }
+                    color += throughput * escaped;
+                    break;
+                }
+            };
+            let mut emitted: Color = rec.mat_ptr.emitted(&ray, &rec);
+            if let (Some(bsdf_pdf), false) = (bsdf_pdf, lights.is_empty()) {
+                // The previous bounce's light sample may have found this emitter too.
+                let light_pdf: f32 = lights.pdf_value(&ray.origin(), &ray.direction());
+                emitted *= path.heuristic.weight(bsdf_pdf, light_pdf);
             }
+            color += throughput * emitted;
+            let srec = match rec.mat_ptr.scatter(&ray, &rec, sampler.rng()) {
+                Some(srec) => srec,
+                None => break,
+            };
+
+            match srec.kind {
+                // Delta lobes carry their whole weight in the attenuation, and no
+                // light sample could ever land on them.
+                ScatterKind::Specular(scattered) => {
+                    throughput *= srec.attenuation;
+                    bsdf_pdf = None;
+                    ray = scattered;
+                }
+                ScatterKind::Diffuse(material_pdf) => {
+                    if !lights.is_empty() {
+                        let to_light: Ray = Ray::with_time(
+                            rec.p,
+                            lights.random(&rec.p, sampler.get_2d()),
+                            ray.time(),
+                        );
+                        let light_pdf: f32 = lights.pdf_value(&rec.p, &to_light.direction());
+                        let scattering_pdf: f32 = rec.mat_ptr.scattering_pdf(&ray, &rec, &to_light);
+                        if light_pdf > 0.0 && scattering_pdf > 0.0 {
+                            if let Some(light_rec) =
+                                world.hit(&to_light, 0.001, INFINITY, sampler.rng())
+                            {
+                                let weight: f32 = path
+                                    .heuristic
+                                    .weight(light_pdf, material_pdf.value(&to_light.direction()));
+                                color += throughput
+                                    * srec.attenuation
+                                    * light_rec.mat_ptr.emitted(&to_light, &light_rec)
+                                    * (weight * scattering_pdf / light_pdf);
+                            }
+                        }
+                    }
 
-            // let target: Point3 = rec.p + Color::random_in_hemisphere(&rec.normal);
-            // let ray: Ray = Ray::new(rec.p, target - rec.p);
-            // return 0.5 * (ray.ray_color(world, depth-1));
+                    let to_sky: Ray =
+                        Ray::with_time(rec.p, environment.random(sampler.get_2d()), ray.time());
+                    let environment_pdf: f32 = environment.pdf_value(&to_sky.direction());
+                    if environment_pdf > 0.0 {
+                        let scattering_pdf: f32 = rec.mat_ptr.scattering_pdf(&ray, &rec, &to_sky);
+                        if scattering_pdf > 0.0
+                            && world.hit(&to_sky, 0.001, INFINITY, sampler.rng()).is_none()
+                        {
+                            let weight: f32 = path
+                                .heuristic
+                                .weight(environment_pdf, material_pdf.value(&to_sky.direction()));
+                            color += throughput
+                                * srec.attenuation
+                                * environment.color(&to_sky.direction())
+                                * (weight * scattering_pdf / environment_pdf);
+                        }
+                    }
+
+                    let scattered: Ray =
+                        Ray::with_time(rec.p, material_pdf.generate(sampler.get_2d()), ray.time());
+                    let pdf_value: f32 = material_pdf.value(&scattered.direction());
+                    if pdf_value <= 0.0 {
+                        break;
+                    }
+                    // Weighted by how the material actually scatters relative to
+                    // how the direction was drawn.
+                    let weight: f32 =
+                        rec.mat_ptr.scattering_pdf(&ray, &rec, &scattered) / pdf_value;
+                    throughput *= srec.attenuation * weight;
+                    bsdf_pdf = Some(pdf_value);
+                    ray = scattered;
+                }
+            }
+
+            if bounce + 1 >= path.min_bounces {
+                // Survival in proportion to the throughput keeps the surviving
+                // paths' weights from growing past 1 / 0.95.
+                let survival: f32 = throughput
+                    .x()
+                    .max(throughput.y())
+                    .max(throughput.z())
+                    .min(0.95);
+                if sampler.get_1d() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
         }
+        color
+    }
+}
 
-        let unit_direction: Vec3 = self.direction().unit_vector();
-        let t: f32 = 0.5 * (unit_direction.y() + 1.0);
-        (1.0 - t) * Color::new(1.0, 1.0, 1.0) + t * Color::new(0.5, 0.7, 1.0)
+/// How far paths go and how their light samples are weighed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathSettings {
+    /// Bounces after which a path always ends.
+    pub max_depth: u16,
+    /// Bounces every path makes, unless it escapes or is absorbed, before
+    /// Russian roulette may end it.
+    pub min_bounces: u16,
+    pub heuristic: MisHeuristic,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            max_depth: 50,
+            min_bounces: 3,
+            heuristic: MisHeuristic::default(),
+        }
     }
 }
 
+pub mod mis;
+
+pub use mis::MisHeuristic;
+
 /**********
  * Unit Test
  ***********/
@@ -60,6 +212,8 @@ impl Ray {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::environment::*;
+    use crate::sampler::IndependentSampler;
     #[test]
     fn test_origin() {
         let (x, y, z) = (1f32, 2f32, 3f32);
@@ -87,6 +241,141 @@ mod tests {
         assert_eq!(r.at(3f32), Point3::new(4f32, 8f32, 12f32));
     }
 
+    #[test]
+    fn test_ray_color_emission_and_background() {
+        use crate::material::*;
+        use crate::object::*;
+        use std::sync::Arc;
+
+        let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(Color::new(4.0, 2.0, 1.0)));
+        let mut world: HittableList = HittableList::new();
+        world.add(Arc::new(Sphere::new(
+            Point3::new(0.0, 0.0, -2.0),
+            0.5,
+            light,
+        )));
+        let black: ConstantEnvironment = ConstantEnvironment::new(Color::new(0.0, 0.0, 0.0));
+        let no_lights: HittableList = HittableList::new();
+        let mut sampler: IndependentSampler = IndependentSampler::new(0);
+
+        let towards: Ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(
+            towards.ray_color(
+                &world,
+                &no_lights,
+                &black,
+                &PathSettings::default(),
+                &mut sampler
+            ),
+            Color::new(4.0, 2.0, 1.0)
+        );
+        let away: Ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            away.ray_color(
+                &world,
+                &no_lights,
+                &black,
+                &PathSettings::default(),
+                &mut sampler
+            ),
+            Color::new(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            away.ray_color(
+                &world,
+                &no_lights,
+                &GradientEnvironment::sky(),
+                &PathSettings::default(),
+                &mut sampler,
+            ),
+            GradientEnvironment::sky().color(&away.direction())
+        );
+    }
+
+    #[test]
+    fn test_russian_roulette_is_unbiased() {
+        use crate::material::*;
+        use crate::object::*;
+        use std::sync::Arc;
+
+        // Everything leaving the half-grey floor escapes to the white sky, so
+        // the floor reflects exactly half of it.
+        let mut world: HittableList = HittableList::new();
+        world.add(Arc::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        let white: ConstantEnvironment = ConstantEnvironment::new(Color::new(1.0, 1.0, 1.0));
+        let no_lights: HittableList = HittableList::new();
+        let down: Ray = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.1, -1.0, 0.0));
+
+        let path: PathSettings = PathSettings {
+            min_bounces: 0,
+            ..PathSettings::default()
+        };
+        let mut sampler: IndependentSampler = IndependentSampler::new(7);
+        let trials: usize = 20000;
+        let mut total: f32 = 0.0;
+        for _ in 0..trials {
+            // Each path either dies at the floor or survives with twice the weight.
+            let c: Color = down.ray_color(&world, &no_lights, &white, &path, &mut sampler);
+            assert!(c.x() == 0.0 || (c.x() - 1.0).abs() < 1e-5, "{:?}", c);
+            total += c.x();
+        }
+        assert!((total / trials as f32 - 0.5).abs() < 0.02);
+
+        // The hard limit still cuts paths off, whatever the throughput.
+        let path: PathSettings = PathSettings {
+            max_depth: 1,
+            ..PathSettings::default()
+        };
+        assert_eq!(
+            down.ray_color(&world, &no_lights, &white, &path, &mut sampler),
+            Color::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_environment_sampling_is_unbiased() {
+        use crate::image::Framebuffer;
+        use crate::material::*;
+        use crate::object::*;
+        use std::sync::Arc;
+
+        let mut world: HittableList = HittableList::new();
+        world.add(Arc::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        // Bright within 45 degrees of straight up and black elsewhere, so the
+        // floor reflects half of 2 * sin²(45°).
+        let mut image: Framebuffer = Framebuffer::new(4, 4);
+        for x in 0..4 {
+            image.set(x, 0, Color::new(2.0, 2.0, 2.0));
+        }
+        let sky: EnvironmentMap = EnvironmentMap::new(image, 0.0, 1.0);
+        let down: Ray = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.1, -1.0, 0.0));
+        let expected: f32 = 0.5 * 2.0 * (PI / 4.0).sin().powi(2);
+
+        let mut sampler: IndependentSampler = IndependentSampler::new(7);
+        let trials: usize = 20000;
+        let mut total: f32 = 0.0;
+        for _ in 0..trials {
+            total += down
+                .ray_color(
+                    &world,
+                    &HittableList::new(),
+                    &sky,
+                    &PathSettings::default(),
+                    &mut sampler,
+                )
+                .x();
+        }
+        assert!((total / trials as f32 - expected).abs() < 0.02, "{}", total);
+    }
+
     // #[test]
     // fn test_hit_sphere_once() {
     //     let center: Point3 = Point3::new(0f32,0f32,0f32);
@@ -0,0 +1,94 @@
+use crate::rtweekend::INFINITY;
+use crate::vec3::*;
+
+/// Adaptive sampling: after its first `samples_per_pixel` samples, a pixel
+/// keeps taking that many more at a time while its relative error exceeds
+/// `threshold`, until it has `max_samples`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveSettings {
+    pub threshold: f32,
+    pub max_samples: u16,
+}
+
+impl AdaptiveSettings {
+    /// Stops at eight times `samples_per_pixel` unless told otherwise.
+    pub fn new(threshold: f32, samples_per_pixel: u16) -> Self {
+        Self {
+            threshold,
+            max_samples: samples_per_pixel.saturating_mul(8),
+        }
+    }
+}
+
+/// Luminance below which a pixel's error is measured in absolute terms, so
+/// that dark pixels need not be sampled forever.
+const DARK_LUMINANCE: f32 = 0.1;
+
+/// The running mean of a pixel's samples, with the variance of their
+/// luminance kept by Welford's method.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PixelStats {
+    count: u32,
+    sum: Color,
+    mean_luminance: f32,
+    /// Sum of squared deviations from `mean_luminance`.
+    m2: f32,
+}
+
+impl PixelStats {
+    pub(crate) fn add(&mut self, sample: Color) {
+        self.count += 1;
+        self.sum += sample;
+        let luminance: f32 = sample.luminance();
+        let delta: f32 = luminance - self.mean_luminance;
+        self.mean_luminance += delta / self.count as f32;
+        self.m2 += delta * (luminance - self.mean_luminance);
+    }
+
+    pub(crate) fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub(crate) fn mean(&self) -> Color {
+        self.sum / self.count.max(1) as f32
+    }
+
+    /// Standard error of the mean luminance relative to the luminance itself,
+    /// or to `DARK_LUMINANCE` if that is larger. It is infinite until there
+    /// are two samples to compare.
+    pub(crate) fn relative_error(&self) -> f32 {
+        if self.count < 2 {
+            return INFINITY;
+        }
+        let n: f32 = self.count as f32;
+        let variance: f32 = self.m2 / (n - 1.0);
+        (variance / n).sqrt() / self.mean_luminance.max(DARK_LUMINANCE)
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_statistics() {
+        let mut stats: PixelStats = PixelStats::default();
+        assert_eq!(stats.relative_error(), INFINITY);
+        for value in [1.0, 2.0, 3.0, 6.0] {
+            stats.add(Color::new(value, value, value));
+        }
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.mean(), Color::new(3.0, 3.0, 3.0));
+        // Variance 14 / 3, so the standard error is sqrt(14 / 12).
+        assert!((stats.relative_error() - (14.0f32 / 12.0).sqrt() / 3.0).abs() < 1e-5);
+
+        let mut dark: PixelStats = PixelStats::default();
+        dark.add(Color::new(0.0, 0.0, 0.0));
+        dark.add(Color::new(0.02, 0.02, 0.02));
+        assert!((dark.relative_error() - 0.1).abs() < 1e-4);
+    }
+}
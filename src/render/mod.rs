@@ -0,0 +1,362 @@
+use crate::camera::*;
+use crate::environment::Environment;
+use crate::image::Framebuffer;
+use crate::object::hittable::Hittable;
+use crate::object::HittableList;
+use crate::ray::PathSettings;
+use crate::sampler::{Sampler, SamplerKind};
+use crate::vec3::*;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use indicatif::ProgressBar;
+
+pub const TILE_SIZE: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tile {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl Tile {
+    /// Splits a `width` x `height` image into row-major tiles of at most
+    /// `TILE_SIZE` x `TILE_SIZE` pixels, starting at the top-left corner.
+    pub fn split(width: usize, height: usize) -> Vec<Tile> {
+        let mut tiles: Vec<Tile> = Vec::new();
+        for y0 in (0..height).step_by(TILE_SIZE) {
+            for x0 in (0..width).step_by(TILE_SIZE) {
+                tiles.push(Tile {
+                    x0,
+                    y0,
+                    x1: (x0 + TILE_SIZE).min(width),
+                    y1: (y0 + TILE_SIZE).min(height),
+                });
+            }
+        }
+        tiles
+    }
+}
+
+pub struct Renderer<'a, T: Hittable> {
+    pub world: &'a T,
+    /// Emitters also in `world` that diffuse bounces aim for directly.
+    pub lights: &'a HittableList,
+    pub cam: &'a Camera,
+    pub environment: &'a dyn Environment,
+    pub path: PathSettings,
+    pub width: usize,
+    pub height: usize,
+    pub samples_per_pixel: u16,
+    /// Lets noisy pixels take more than `samples_per_pixel` samples.
+    pub adaptive: Option<AdaptiveSettings>,
+    pub sampler: SamplerKind,
+    pub threads: usize,
+    /// Every pixel sample draws from its own streams of this seed, so the
+    /// image is the same whatever the thread count.
+    pub seed: u64,
+}
+
+impl<'a, T: Hittable> Renderer<'a, T> {
+    /// Renders the image on `threads` worker threads. Worker threads pull tiles off a
+    /// shared counter until none are left, so fast tiles never wait on slow ones.
+    /// Each pixel holds the mean linear radiance of its samples.
+    pub fn render(&self) -> Rendered {
+        let tiles: Vec<Tile> = Tile::split(self.width, self.height);
+        let next_tile: AtomicUsize = AtomicUsize::new(0);
+        let rendered: Mutex<Rendered> = Mutex::new(Rendered {
+            image: Framebuffer::new(self.width, self.height),
+            samples: vec![0; self.width * self.height],
+        });
+
+        let pb: ProgressBar = ProgressBar::new(tiles.len() as u64);
+        thread::scope(|s| {
+            for _ in 0..self.threads.max(1) {
+                let (next_tile, rendered, tiles, pb) = (&next_tile, &rendered, &tiles, &pb);
+                s.spawn(move || loop {
+                    let index: usize = next_tile.fetch_add(1, Ordering::Relaxed);
+                    let tile: Tile = match tiles.get(index) {
+                        Some(tile) => *tile,
+                        None => break,
+                    };
+                    let pixels: Vec<PixelStats> = self.render_tile(&tile);
+
+                    let mut rendered = rendered.lock().unwrap();
+                    let tile_width: usize = tile.x1 - tile.x0;
+                    for (k, stats) in pixels.into_iter().enumerate() {
+                        let (x, y) = (tile.x0 + k % tile_width, tile.y0 + k / tile_width);
+                        rendered.image.set(x, y, stats.mean());
+                        rendered.samples[y * self.width + x] = stats.count() as u16;
+                    }
+                    pb.inc(1);
+                });
+            }
+        });
+        pb.finish_with_message("Done");
+
+        rendered.into_inner().unwrap()
+    }
+
+    /// The most samples any pixel takes.
+    pub fn max_samples(&self) -> u16 {
+        self.adaptive.map_or(self.samples_per_pixel, |adaptive| {
+            adaptive.max_samples.max(self.samples_per_pixel)
+        })
+    }
+
+    /// A sampler that spreads each batch of `samples_per_pixel` samples evenly,
+    /// so that pixels which stop after their first batch are as well sampled
+    /// as in a render without adaptive sampling.
+    fn pixel_sampler(&self) -> Box<dyn Sampler> {
+        self.sampler
+            .sampler(self.samples_per_pixel as u32, self.seed)
+    }
+
+    fn render_tile(&self, tile: &Tile) -> Vec<PixelStats> {
+        let max_samples: u32 = self.max_samples() as u32;
+        let mut sampler: Box<dyn Sampler> = self.pixel_sampler();
+        let mut pixels: Vec<PixelStats> =
+            Vec::with_capacity((tile.x1 - tile.x0) * (tile.y1 - tile.y0));
+        for y in tile.y0..tile.y1 {
+            for x in tile.x0..tile.x1 {
+                let mut stats: PixelStats = PixelStats::default();
+                loop {
+                    let batch_end: u32 =
+                        (stats.count() + self.samples_per_pixel as u32).min(max_samples);
+                    while stats.count() < batch_end {
+                        stats.add(self.sample(x, y, stats.count(), sampler.as_mut()));
+                    }
+                    let noisy: bool = self
+                        .adaptive
+                        .is_some_and(|adaptive| stats.relative_error() > adaptive.threshold);
+                    if !noisy || stats.count() >= max_samples {
+                        break;
+                    }
+                }
+                pixels.push(stats);
+            }
+        }
+        pixels
+    }
+
+    /// Radiance along sample `index` of the pixel at `(x, y)`.
+    fn sample(&self, x: usize, y: usize, index: u32, sampler: &mut dyn Sampler) -> Color {
+        sampler.start_pixel_sample(x, y, index);
+        let (du, dv) = sampler.get_2d();
+        // Image rows are stored top-down, but v grows upwards.
+        let u: f32 = (x as f32 + du) / self.width as f32;
+        let v: f32 = ((self.height - 1 - y) as f32 + dv) / self.height as f32;
+        self.cam.get_ray(u, v, sampler).ray_color(
+            self.world,
+            self.lights,
+            self.environment,
+            &self.path,
+            sampler,
+        )
+    }
+}
+
+/// A rendered image and the number of samples each of its pixels took.
+pub struct Rendered {
+    pub image: Framebuffer,
+    /// Row by row, like the image.
+    pub samples: Vec<u16>,
+}
+
+impl Rendered {
+    /// The sample counts as a grey image for inspection, black for none and
+    /// white for `max_samples` or more.
+    pub fn sample_map(&self, max_samples: u16) -> Framebuffer {
+        let pixels: Vec<Color> = self
+            .samples
+            .iter()
+            .map(|&count| {
+                let level: f32 = (count as f32 / max_samples.max(1) as f32).min(1.0);
+                Color::new(level, level, level)
+            })
+            .collect();
+        Framebuffer::from_pixels(self.image.width(), self.image.height(), pixels)
+    }
+}
+
+pub mod adaptive;
+
+pub use adaptive::AdaptiveSettings;
+
+use adaptive::PixelStats;
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_covers_image() {
+        let tiles: Vec<Tile> = Tile::split(40, 20);
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(
+            tiles[0],
+            Tile {
+                x0: 0,
+                y0: 0,
+                x1: 16,
+                y1: 16
+            }
+        );
+        assert_eq!(
+            tiles[5],
+            Tile {
+                x0: 32,
+                y0: 16,
+                x1: 40,
+                y1: 20
+            }
+        );
+        let area: usize = tiles.iter().map(|t| (t.x1 - t.x0) * (t.y1 - t.y0)).sum();
+        assert_eq!(area, 40 * 20);
+    }
+
+    #[test]
+    fn test_seed_fixes_image_whatever_the_threads() {
+        use crate::rtweekend::Rng;
+        use crate::scene::{builtin_scene, Scene};
+
+        let scene: Scene = builtin_scene("cornell_smoke", &mut Rng::new(0)).unwrap();
+        let cam: Camera = scene.camera.camera(1.0);
+        let render = |threads: usize, seed: u64| {
+            Renderer {
+                world: &scene.world,
+                lights: &scene.lights,
+                cam: &cam,
+                environment: scene.environment.as_ref(),
+                path: scene.settings.path(),
+                width: 20,
+                height: 20,
+                samples_per_pixel: 2,
+                adaptive: None,
+                sampler: SamplerKind::Sobol,
+                threads,
+                seed,
+            }
+            .render()
+            .image
+        };
+        let image: Framebuffer = render(1, 5);
+        assert_eq!(render(3, 5), image);
+        assert_ne!(render(1, 6), image);
+    }
+
+    #[test]
+    fn test_single_row_and_column_images() {
+        use crate::rtweekend::Rng;
+        use crate::scene::{builtin_scene, Scene};
+
+        let scene: Scene = builtin_scene("cornell", &mut Rng::new(0)).unwrap();
+        let cam: Camera = scene.camera.camera(1.0);
+        for (width, height) in [(1, 6), (6, 1), (1, 1)] {
+            let image: Framebuffer = Renderer {
+                world: &scene.world,
+                lights: &scene.lights,
+                cam: &cam,
+                environment: scene.environment.as_ref(),
+                path: scene.settings.path(),
+                width,
+                height,
+                samples_per_pixel: 4,
+                adaptive: None,
+                sampler: SamplerKind::Independent,
+                threads: 1,
+                seed: 3,
+            }
+            .render()
+            .image;
+            // Every pixel looks into the lit box rather than off into the dark.
+            assert!(image.pixels().iter().all(|c| {
+                c.x().is_finite() && c.y().is_finite() && c.z().is_finite() && c.luminance() > 0.0
+            }));
+        }
+    }
+
+    #[test]
+    fn test_adaptive_batches_are_each_stratified() {
+        use crate::rtweekend::Rng;
+        use crate::scene::{builtin_scene, Scene};
+
+        let scene: Scene = builtin_scene("cornell", &mut Rng::new(0)).unwrap();
+        let cam: Camera = scene.camera.camera(1.0);
+        let renderer = Renderer {
+            world: &scene.world,
+            lights: &scene.lights,
+            cam: &cam,
+            environment: scene.environment.as_ref(),
+            path: scene.settings.path(),
+            width: 8,
+            height: 8,
+            samples_per_pixel: 4,
+            adaptive: Some(AdaptiveSettings {
+                threshold: 0.05,
+                max_samples: 32,
+            }),
+            sampler: SamplerKind::Stratified,
+            threads: 1,
+            seed: 1,
+        };
+        let mut sampler: Box<dyn Sampler> = renderer.pixel_sampler();
+        for batch in 0..2 {
+            // The position in the pixel falls in each quarter once per batch.
+            let mut cells: [usize; 4] = [0; 4];
+            for index in 4 * batch..4 * (batch + 1) {
+                sampler.start_pixel_sample(3, 5, index);
+                let (u, v) = sampler.get_2d();
+                cells[(v * 2.0) as usize * 2 + (u * 2.0) as usize] += 1;
+            }
+            assert_eq!(cells, [1; 4]);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_sampling_spends_samples_on_noise() {
+        use crate::rtweekend::Rng;
+        use crate::scene::{builtin_scene, Scene};
+
+        let scene: Scene = builtin_scene("cornell", &mut Rng::new(0)).unwrap();
+        let cam: Camera = scene.camera.camera(1.0);
+        let renderer = Renderer {
+            world: &scene.world,
+            lights: &scene.lights,
+            cam: &cam,
+            environment: scene.environment.as_ref(),
+            path: scene.settings.path(),
+            width: 24,
+            height: 24,
+            samples_per_pixel: 4,
+            adaptive: Some(AdaptiveSettings {
+                threshold: 0.05,
+                max_samples: 64,
+            }),
+            sampler: SamplerKind::Independent,
+            threads: 2,
+            seed: 1,
+        };
+        let rendered: Rendered = renderer.render();
+        // The ceiling light looks the same to every sample; the walls do not.
+        let fewest: u16 = *rendered.samples.iter().min().unwrap();
+        let most: u16 = *rendered.samples.iter().max().unwrap();
+        assert_eq!((fewest, most), (4, 64));
+        assert!(rendered.samples.iter().all(|count| count % 4 == 0));
+
+        let map: Framebuffer = rendered.sample_map(renderer.max_samples());
+        let light: usize = rendered.samples.iter().position(|&c| c == 4).unwrap();
+        assert_eq!(
+            map.get(light % 24, light / 24),
+            Color::new(0.0625, 0.0625, 0.0625)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+extern crate rand;
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng};
+
+pub const INFINITY: f32 = f32::INFINITY;
+pub const PI: f32 = std::f32::consts::PI;
+
+pub fn degree_to_radians(degrees: f32) -> f32 {
+    degrees * PI / 180.0
+}
+
+/// A seedable stream of random numbers, handed down to everything that
+/// samples. Renders give each pixel its own stream derived from one global
+/// seed, so an image depends only on that seed and not on which thread drew
+/// which pixel.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    inner: StdRng,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Stream number `stream` of the family seeded by `seed`, e.g. one per
+    /// pixel. Neighbouring streams are unrelated.
+    pub fn for_stream(seed: u64, stream: u64) -> Self {
+        Rng::new(split_mix(seed ^ split_mix(stream)))
+    }
+
+    /// A seed taken from system entropy, for runs that need not be repeatable.
+    pub fn random_seed() -> u64 {
+        rand::random()
+    }
+
+    pub fn random_double_with_range(&mut self, min: f32, max: f32) -> f32 {
+        self.inner.gen_range(min, max)
+    }
+
+    pub fn random_double(&mut self) -> f32 {
+        self.random_double_with_range(0.0, 1.0)
+    }
+}
+
+/// Steele, Lea and Flood's SplitMix64 finalizer: scrambles every bit of `x`
+/// into every bit of the result.
+pub(crate) fn split_mix(x: u64) -> u64 {
+    let mut z: u64 = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+pub fn clamp(x: f32, min: f32, max: f32) -> f32 {
+    if x < min {
+        min
+    } else if x > max {
+        max
+    } else {
+        x
+    }
+}
+
+pub mod perlin;
+
+pub use perlin::Perlin;
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streams_are_repeatable() {
+        let draw = |mut rng: Rng| -> Vec<f32> { (0..4).map(|_| rng.random_double()).collect() };
+        assert_eq!(draw(Rng::new(7)), draw(Rng::new(7)));
+        assert_ne!(draw(Rng::new(7)), draw(Rng::new(8)));
+        assert_eq!(draw(Rng::for_stream(7, 3)), draw(Rng::for_stream(7, 3)));
+        assert_ne!(draw(Rng::for_stream(7, 3)), draw(Rng::for_stream(7, 4)));
+        assert_ne!(draw(Rng::for_stream(7, 3)), draw(Rng::for_stream(3, 7)));
+    }
+}
@@ -0,0 +1,129 @@
+use super::*;
+use crate::vec3::*;
+use rand::seq::SliceRandom;
+
+const POINT_COUNT: usize = 256;
+
+/// Perlin gradient noise. The lattice of random gradients and permutations is
+/// drawn from its own generator, so the same seed always gives the same noise
+/// no matter what else has consumed random numbers.
+pub struct Perlin {
+    ranvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng: StdRng = StdRng::seed_from_u64(seed);
+        let ranvec: Vec<Vec3> = (0..POINT_COUNT)
+            .map(|_| loop {
+                let v: Vec3 = Vec3::new(
+                    rng.gen_range(-1.0, 1.0),
+                    rng.gen_range(-1.0, 1.0),
+                    rng.gen_range(-1.0, 1.0),
+                );
+                // Rejecting the cube's corners keeps the directions uniform.
+                if v.length_squared() > 1e-4 && v.length_squared() <= 1.0 {
+                    break v.unit_vector();
+                }
+            })
+            .collect();
+        let mut permute = || {
+            let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+            p.shuffle(&mut rng);
+            p
+        };
+        Self {
+            perm_x: permute(),
+            perm_y: permute(),
+            perm_z: permute(),
+            ranvec,
+        }
+    }
+
+    /// Smooth noise in [-1, 1], zero at every integer lattice point.
+    pub fn noise(&self, p: &Point3) -> f32 {
+        let (fx, fy, fz) = (p.x().floor(), p.y().floor(), p.z().floor());
+        let (u, v, w) = (p.x() - fx, p.y() - fy, p.z() - fz);
+        let (i, j, k) = (fx as i64, fy as i64, fz as i64);
+        // Hermite smoothing hides the lattice's grid lines.
+        let (uu, vv, ww) = (
+            u * u * (3.0 - 2.0 * u),
+            v * v * (3.0 - 2.0 * v),
+            w * w * (3.0 - 2.0 * w),
+        );
+
+        let mut accum: f32 = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let gradient: Vec3 = self.ranvec[self.perm_x[((i + di) & 255) as usize]
+                        ^ self.perm_y[((j + dj) & 255) as usize]
+                        ^ self.perm_z[((k + dk) & 255) as usize]];
+                    let (a, b, c) = (di as f32, dj as f32, dk as f32);
+                    let weight: Vec3 = Vec3::new(u - a, v - b, w - c);
+                    accum += (a * uu + (1.0 - a) * (1.0 - uu))
+                        * (b * vv + (1.0 - b) * (1.0 - vv))
+                        * (c * ww + (1.0 - c) * (1.0 - ww))
+                        * gradient.dot(&weight);
+                }
+            }
+        }
+        accum
+    }
+
+    /// Fractal Brownian motion: `octaves` layers of noise, each at twice the
+    /// frequency and half the amplitude of the previous one.
+    pub fn fbm(&self, p: &Point3, octaves: u32) -> f32 {
+        self.octaves(p, octaves, |n| n)
+    }
+
+    /// Like `fbm` but summing absolute values, which folds the noise into the
+    /// sharp creases of turbulence. The result is non-negative.
+    pub fn turbulence(&self, p: &Point3, octaves: u32) -> f32 {
+        self.octaves(p, octaves, f32::abs)
+    }
+
+    fn octaves(&self, p: &Point3, octaves: u32, f: impl Fn(f32) -> f32) -> f32 {
+        let mut accum: f32 = 0.0;
+        let mut temp_p: Point3 = *p;
+        let mut weight: f32 = 1.0;
+        for _ in 0..octaves {
+            accum += weight * f(self.noise(&temp_p));
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+        accum
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded() {
+        let p: Point3 = Point3::new(1.3, -0.7, 2.45);
+        assert_eq!(Perlin::new(7).noise(&p), Perlin::new(7).noise(&p));
+        assert_ne!(Perlin::new(7).noise(&p), Perlin::new(8).noise(&p));
+    }
+
+    #[test]
+    fn test_range_and_lattice() {
+        let perlin: Perlin = Perlin::new(1);
+        assert_eq!(perlin.noise(&Point3::new(3.0, -2.0, 5.0)), 0.0);
+        for i in 0..1000 {
+            let t: f32 = i as f32 * 0.137;
+            let p: Point3 = Point3::new(t, t * 0.7 - 3.0, t * 1.3 + 0.5);
+            assert!(perlin.noise(&p).abs() <= 1.0);
+            assert!(perlin.turbulence(&p, 7) >= 0.0);
+            assert!(perlin.fbm(&p, 7).abs() < 2.0);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+/// Dimensions with a Halton base of their own; later ones are independent.
+const PRIME_COUNT: usize = 128;
+const PRIMES: [u32; PRIME_COUNT] = first_primes();
+
+/// The Halton sequence: dimension `d` of sample `i` reverses the digits of
+/// `i` in the `d`-th prime base. Each pixel shifts every dimension by its own
+/// random offset, wrapping around, so neighbouring pixels do not share a
+/// pattern.
+#[derive(Clone, Debug)]
+pub struct HaltonSampler {
+    state: SampleState,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: SampleState::new(seed),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (dimension, hash) = self.state.next_dimension();
+        match PRIMES.get(dimension as usize) {
+            Some(&base) => {
+                let offset: f32 = to_unit((hash >> 32) as u32);
+                (radical_inverse(base, self.state.index) + offset)
+                    .fract()
+                    .min(1.0 - f32::EPSILON)
+            }
+            None => self.state.rng.random_double(),
+        }
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.get_1d(), self.get_1d())
+    }
+
+    fn rng(&mut self) -> &mut Rng {
+        &mut self.state.rng
+    }
+}
+
+/// `index` written in `base` and mirrored about the radix point.
+fn radical_inverse(base: u32, mut index: u32) -> f32 {
+    let inverse_base: f64 = 1.0 / base as f64;
+    let (mut reversed, mut scale) = (0.0f64, inverse_base);
+    while index > 0 {
+        reversed += (index % base) as f64 * scale;
+        index /= base;
+        scale *= inverse_base;
+    }
+    reversed as f32
+}
+
+const fn first_primes() -> [u32; PRIME_COUNT] {
+    let mut primes: [u32; PRIME_COUNT] = [0; PRIME_COUNT];
+    let (mut count, mut candidate) = (0, 2);
+    while count < PRIME_COUNT {
+        let mut i: usize = 0;
+        while i < count && candidate % primes[i] != 0 {
+            i += 1;
+        }
+        if i == count {
+            primes[count] = candidate;
+            count += 1;
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radical_inverse() {
+        assert_eq!(PRIMES[..5], [2, 3, 5, 7, 11]);
+        assert_eq!(radical_inverse(2, 0), 0.0);
+        assert_eq!(radical_inverse(2, 6), 0.375);
+        assert!((radical_inverse(3, 5) - 7.0 / 9.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_shifted_sequence_stays_stratified() {
+        let mut sampler: HaltonSampler = HaltonSampler::new(2);
+        let mut strata: Vec<usize> = vec![0; 8];
+        for index in 0..8 {
+            sampler.start_pixel_sample(1, 7, index);
+            strata[(sampler.get_1d() * 8.0) as usize] += 1;
+        }
+        assert!(strata.iter().all(|&count| count == 1));
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// Independent uniform random numbers in every dimension: the plain Monte
+/// Carlo the other samplers improve on.
+#[derive(Clone, Debug)]
+pub struct IndependentSampler {
+    state: SampleState,
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: SampleState::new(seed),
+        }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        self.state.rng.random_double()
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.get_1d(), self.get_1d())
+    }
+
+    fn rng(&mut self) -> &mut Rng {
+        &mut self.state.rng
+    }
+}
@@ -0,0 +1,183 @@
+use crate::rtweekend::*;
+
+/// Supplies the random numbers of one pixel sample at a time. Each call to
+/// `get_1d` or `get_2d` claims the next dimension of the current sample.
+/// Every sampler but `IndependentSampler` spreads the samples of a pixel
+/// evenly over each dimension, so images converge faster than with
+/// independent random numbers.
+///
+/// A sample uses its first dimensions for the position in the pixel, the lens
+/// and the shutter time, then a few more at each vertex of its path.
+pub trait Sampler {
+    /// Begins sample `index` of the pixel at `(x, y)`.
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: u32);
+
+    fn get_1d(&mut self) -> f32;
+
+    fn get_2d(&mut self) -> (f32, f32);
+
+    /// Random numbers for choices made too irregularly to stratify, such as
+    /// where light scatters in a medium. Like the dimensions, they depend
+    /// only on the seed, the pixel and the sample index.
+    fn rng(&mut self) -> &mut Rng;
+}
+
+/// The samplers a render can choose from.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SamplerKind {
+    /// Independent uniform random numbers.
+    #[default]
+    Independent,
+    /// Jittered strata, one per sample in each dimension.
+    Stratified,
+    /// The Halton sequence, randomly shifted in each pixel.
+    Halton,
+    /// The Sobol sequence with Owen scrambling.
+    Sobol,
+}
+
+impl SamplerKind {
+    /// A sampler of this kind for pixels of `samples_per_pixel` samples, drawing
+    /// from streams of `seed`.
+    pub fn sampler(&self, samples_per_pixel: u32, seed: u64) -> Box<dyn Sampler> {
+        match self {
+            SamplerKind::Independent => Box::new(IndependentSampler::new(seed)),
+            SamplerKind::Stratified => Box::new(StratifiedSampler::new(samples_per_pixel, seed)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(seed)),
+        }
+    }
+}
+
+/// The pixel sample a sampler is on and how many of its dimensions are used.
+#[derive(Clone, Debug)]
+struct SampleState {
+    seed: u64,
+    /// Hash of the seed and the pixel.
+    pixel: u64,
+    index: u32,
+    dimension: u32,
+    rng: Rng,
+}
+
+impl SampleState {
+    fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            pixel: 0,
+            index: 0,
+            dimension: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    fn start(&mut self, x: usize, y: usize, index: u32) {
+        self.pixel = hash(self.seed, &[x as u64, y as u64]);
+        self.index = index;
+        self.dimension = 0;
+        self.rng = Rng::for_stream(self.seed, hash(self.pixel, &[index as u64]));
+    }
+
+    /// Claims the next dimension, returning it and a hash of it and the pixel.
+    fn next_dimension(&mut self) -> (u32, u64) {
+        let dimension: u32 = self.dimension;
+        self.dimension += 1;
+        (dimension, hash(self.pixel, &[dimension as u64]))
+    }
+}
+
+fn hash(seed: u64, values: &[u64]) -> u64 {
+    values
+        .iter()
+        .fold(split_mix(seed), |h, value| split_mix(h ^ value))
+}
+
+/// The fraction in [0, 1) whose binary digits are those of `bits`.
+fn to_unit(bits: u32) -> f32 {
+    (bits >> 8) as f32 / (1u32 << 24) as f32
+}
+
+pub mod halton;
+pub mod independent;
+pub mod sobol;
+pub mod stratified;
+
+pub use halton::HaltonSampler;
+pub use independent::IndependentSampler;
+pub use sobol::SobolSampler;
+pub use stratified::StratifiedSampler;
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mean squared error, over many pixels, of estimating the area of the
+    /// quarter disk with `samples` points each.
+    fn quarter_disk_error(kind: SamplerKind, samples: u32) -> f32 {
+        let mut sampler: Box<dyn Sampler> = kind.sampler(samples, 3);
+        let pixels: usize = 400;
+        let mut error: f32 = 0.0;
+        for x in 0..pixels {
+            let mut inside: u32 = 0;
+            for index in 0..samples {
+                sampler.start_pixel_sample(x, 0, index);
+                // Skip the pixel's own dimensions, as a path would.
+                sampler.get_2d();
+                let (u, v) = sampler.get_2d();
+                assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+                if u * u + v * v < 1.0 {
+                    inside += 1;
+                }
+            }
+            error += (inside as f32 / samples as f32 - PI / 4.0).powi(2);
+        }
+        error / pixels as f32
+    }
+
+    #[test]
+    fn test_low_discrepancy_beats_independent() {
+        let independent: f32 = quarter_disk_error(SamplerKind::Independent, 64);
+        for kind in [
+            SamplerKind::Stratified,
+            SamplerKind::Halton,
+            SamplerKind::Sobol,
+        ] {
+            let error: f32 = quarter_disk_error(kind, 64);
+            assert!(
+                error < independent / 4.0,
+                "{:?}: {} vs {}",
+                kind,
+                error,
+                independent
+            );
+        }
+    }
+
+    #[test]
+    fn test_samples_are_repeatable() {
+        for kind in [
+            SamplerKind::Independent,
+            SamplerKind::Stratified,
+            SamplerKind::Halton,
+            SamplerKind::Sobol,
+        ] {
+            let mut sampler: Box<dyn Sampler> = kind.sampler(16, 9);
+            let mut draw = |x: usize, index: u32| {
+                sampler.start_pixel_sample(x, 2, index);
+                (
+                    sampler.get_1d(),
+                    sampler.get_2d(),
+                    sampler.rng().random_double(),
+                )
+            };
+            let first = draw(5, 3);
+            draw(6, 0);
+            assert_eq!(draw(5, 3), first);
+            assert_ne!(draw(5, 4), first);
+        }
+    }
+}
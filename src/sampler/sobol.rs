@@ -0,0 +1,138 @@
+use super::*;
+
+/// Generator matrices of the first two Sobol dimensions, column by column.
+const DIRECTIONS: [[u32; 32]; 2] = directions();
+
+/// The Sobol sequence with Owen scrambling, after Burley, "Practical
+/// Hash-based Owen Scrambling" (2020). Each dimension of a path is a 1D or
+/// 2D Sobol sequence of its own, shuffled and scrambled by a hash of the
+/// pixel and the dimension, so it stays well stratified however many
+/// dimensions a path uses. Sample counts that are powers of two work best.
+#[derive(Clone, Debug)]
+pub struct SobolSampler {
+    state: SampleState,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: SampleState::new(seed),
+        }
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (_, hash) = self.state.next_dimension();
+        let index: u32 = nested_uniform_scramble(self.state.index, hash as u32);
+        to_unit(nested_uniform_scramble(
+            sobol(index, 0),
+            (hash >> 32) as u32,
+        ))
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let (_, hash) = self.state.next_dimension();
+        let index: u32 = nested_uniform_scramble(self.state.index, hash as u32);
+        let scramble: u64 = split_mix(hash);
+        (
+            to_unit(nested_uniform_scramble(sobol(index, 0), scramble as u32)),
+            to_unit(nested_uniform_scramble(
+                sobol(index, 1),
+                (scramble >> 32) as u32,
+            )),
+        )
+    }
+
+    fn rng(&mut self) -> &mut Rng {
+        &mut self.state.rng
+    }
+}
+
+/// Dimension `dimension` of Sobol point `index`, as a binary fraction.
+fn sobol(index: u32, dimension: usize) -> u32 {
+    (0..32)
+        .filter(|bit| index >> bit & 1 == 1)
+        .fold(0, |x, bit| x ^ DIRECTIONS[dimension][bit])
+}
+
+/// Owen scrambling of the binary fraction `x`: each digit is flipped or not
+/// according to `seed` and the digits before it.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// Flips each bit of `x` by a hash of `seed` and the bits below it.
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x
+}
+
+/// The first dimension reverses the bits of the index; the second is Pascal's
+/// triangle modulo 2.
+const fn directions() -> [[u32; 32]; 2] {
+    let mut directions: [[u32; 32]; 2] = [[0; 32]; 2];
+    let mut v: u32 = 1 << 31;
+    let mut bit: usize = 0;
+    while bit < 32 {
+        directions[0][bit] = 1 << (31 - bit);
+        directions[1][bit] = v;
+        v ^= v >> 1;
+        bit += 1;
+    }
+    directions
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unscrambled_points() {
+        let points: Vec<(u32, u32)> = (0..4).map(|i| (sobol(i, 0), sobol(i, 1))).collect();
+        let quarter: u32 = 1 << 30;
+        assert_eq!(
+            points,
+            [
+                (0, 0),
+                (2 * quarter, 2 * quarter),
+                (quarter, 3 * quarter),
+                (3 * quarter, quarter)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scrambled_points_fill_elementary_intervals() {
+        let mut sampler: SobolSampler = SobolSampler::new(4);
+        // The first 16 points fill each of the 16 boxes of every shape
+        // 1x16, 2x8, 4x4, 8x2 and 16x1 exactly once.
+        let points: Vec<(f32, f32)> = (0..16)
+            .map(|index| {
+                sampler.start_pixel_sample(3, 8, index);
+                sampler.get_1d();
+                sampler.get_2d()
+            })
+            .collect();
+        for columns in [1, 2, 4, 8, 16] {
+            let rows: usize = 16 / columns;
+            let mut boxes: Vec<usize> = vec![0; 16];
+            for (u, v) in &points {
+                boxes[(v * rows as f32) as usize * columns + (u * columns as f32) as usize] += 1;
+            }
+            assert!(boxes.iter().all(|&count| count == 1), "{} columns", columns);
+        }
+    }
+}
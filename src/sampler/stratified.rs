@@ -0,0 +1,146 @@
+use super::*;
+
+/// Splits each dimension into one stratum per sample and jitters each sample
+/// within its stratum; 2D dimensions are split into a grid as close to square
+/// as the sample count allows. Every dimension visits its strata in its own
+/// random order, so that no two dimensions are correlated.
+#[derive(Clone, Debug)]
+pub struct StratifiedSampler {
+    samples_per_pixel: u32,
+    /// Columns of the 2D grid; its rows are `samples_per_pixel / columns`.
+    columns: u32,
+    state: SampleState,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        assert!(
+            samples_per_pixel > 0,
+            "StratifiedSampler needs at least one sample per pixel."
+        );
+        let columns: u32 = (1..=samples_per_pixel)
+            .take_while(|c| c * c <= samples_per_pixel)
+            .filter(|c| samples_per_pixel.is_multiple_of(*c))
+            .last()
+            .unwrap_or(1);
+        Self {
+            samples_per_pixel,
+            columns,
+            state: SampleState::new(seed),
+        }
+    }
+
+    /// The stratum the current sample takes in the next dimension. Each
+    /// further batch of `samples_per_pixel` samples covers the strata again,
+    /// in an order of its own.
+    fn next_stratum(&mut self) -> u32 {
+        let (_, dimension_hash) = self.state.next_dimension();
+        let batch: u32 = self.state.index / self.samples_per_pixel;
+        permutation_element(
+            self.state.index % self.samples_per_pixel,
+            self.samples_per_pixel,
+            hash(dimension_hash, &[batch as u64]) as u32,
+        )
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let stratum: u32 = self.next_stratum();
+        let jitter: f32 = self.state.rng.random_double();
+        ((stratum as f32 + jitter) / self.samples_per_pixel as f32).min(1.0 - f32::EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let stratum: u32 = self.next_stratum();
+        let rows: u32 = self.samples_per_pixel / self.columns;
+        let (column, row) = (stratum % self.columns, stratum / self.columns);
+        let jitter: (f32, f32) = (
+            self.state.rng.random_double(),
+            self.state.rng.random_double(),
+        );
+        (
+            ((column as f32 + jitter.0) / self.columns as f32).min(1.0 - f32::EPSILON),
+            ((row as f32 + jitter.1) / rows as f32).min(1.0 - f32::EPSILON),
+        )
+    }
+
+    fn rng(&mut self) -> &mut Rng {
+        &mut self.state.rng
+    }
+}
+
+/// Element `i` of a random permutation of `0..n` chosen by `seed`, without
+/// building the permutation; after Kensler, "Correlated Multi-Jittered
+/// Sampling" (2013).
+fn permutation_element(mut i: u32, n: u32, seed: u32) -> u32 {
+    let mut w: u32 = n - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    // A bijection on 0..=w, repeated until it lands inside 0..n.
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < n {
+            break;
+        }
+    }
+    (i + seed) % n
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permutation_element_is_a_permutation() {
+        for (n, seed) in [(1, 7), (12, 1), (64, 0xdead_beef)] {
+            let mut seen: Vec<u32> = (0..n).map(|i| permutation_element(i, n, seed)).collect();
+            seen.sort_unstable();
+            assert_eq!(seen, (0..n).collect::<Vec<u32>>());
+        }
+    }
+
+    #[test]
+    fn test_every_stratum_gets_one_sample() {
+        let mut sampler: StratifiedSampler = StratifiedSampler::new(12, 1);
+        assert_eq!(sampler.columns, 3);
+        let mut rows: Vec<usize> = vec![0; 12];
+        let mut cells: Vec<usize> = vec![0; 12];
+        for index in 0..12 {
+            sampler.start_pixel_sample(4, 4, index);
+            rows[(sampler.get_1d() * 12.0) as usize] += 1;
+            let (u, v) = sampler.get_2d();
+            cells[(v * 4.0) as usize * 3 + (u * 3.0) as usize] += 1;
+        }
+        assert!(rows.iter().all(|&count| count == 1));
+        assert!(cells.iter().all(|&count| count == 1));
+    }
+}
@@ -0,0 +1,246 @@
+use super::*;
+use crate::rtweekend::*;
+
+/// Names accepted by `builtin_scene`.
+pub const BUILTIN_SCENES: &[&str] = &["random", "bouncing", "sunlit", "cornell", "cornell_smoke"];
+
+/// The scene called `name`. Scenes with randomly placed objects draw them
+/// from `rng`.
+pub fn builtin_scene(name: &str, rng: &mut Rng) -> Option<Scene> {
+    match name {
+        "random" => Some(random_scene(rng)),
+        "bouncing" => Some(bouncing_spheres(rng)),
+        "sunlit" => Some(sunlit_spheres(rng)),
+        "cornell" => Some(cornell_box()),
+        "cornell_smoke" => Some(cornell_smoke()),
+        _ => None,
+    }
+}
+
+/// The final scene of "Ray Tracing in One Weekend": a field of small random
+/// spheres around three large ones.
+pub fn random_scene(rng: &mut Rng) -> Scene {
+    sphere_field(false, rng)
+}
+
+/// The random scene with its diffuse spheres bouncing upwards while the
+/// shutter is open, the first scene of "Ray Tracing: The Next Week".
+pub fn bouncing_spheres(rng: &mut Rng) -> Scene {
+    sphere_field(true, rng)
+}
+
+/// The random scene in late afternoon sun under a physical sky.
+pub fn sunlit_spheres(rng: &mut Rng) -> Scene {
+    Scene {
+        environment: Arc::new(PhysicalSky::new(25.0, 110.0, 3.0)),
+        ..sphere_field(false, rng)
+    }
+}
+
+fn sphere_field(bouncing: bool, rng: &mut Rng) -> Scene {
+    let mut world: HittableList = HittableList::new();
+    let ground_material: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+
+    world.add(Arc::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let choose_mat: f32 = rng.random_double();
+            let center: Point3 = Point3::new(
+                a as f32 + 0.9 * rng.random_double(),
+                0.2,
+                b as f32 + 0.9 * rng.random_double(),
+            );
+
+            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
+                if choose_mat < 0.8 {
+                    let albedo: Arc<dyn Material> =
+                        Arc::new(Lambertian::new(Color::random(rng) * Color::random(rng)));
+                    if bouncing {
+                        let center1: Point3 =
+                            center + Vec3::new(0.0, rng.random_double_with_range(0.0, 0.5), 0.0);
+                        world.add(Arc::new(MovingSphere::new(
+                            center, center1, 0.0, 1.0, 0.2, albedo,
+                        )));
+                    } else {
+                        world.add(Arc::new(Sphere::new(center, 0.2, albedo)));
+                    }
+                } else {
+                    world.add(Arc::new(Sphere::new(
+                        center,
+                        0.2,
+                        if choose_mat < 0.95 {
+                            Arc::new(Metal::new(
+                                Color::random_with_range(0.5, 1.0, rng),
+                                rng.random_double_with_range(0.0, 0.5),
+                            ))
+                        } else {
+                            Arc::new(Dielectric::new(1.5))
+                        },
+                    )));
+                }
+            }
+        }
+    }
+
+    let material1: Arc<dyn Material> = Arc::new(Dielectric::new(1.5));
+    world.add(Arc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        material1,
+    )));
+
+    let material2: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
+    world.add(Arc::new(Sphere::new(
+        Point3::new(-4.0, 1.0, 0.0),
+        1.0,
+        material2,
+    )));
+
+    let material3: Arc<dyn Material> = Arc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
+    world.add(Arc::new(Sphere::new(
+        Point3::new(4.0, 1.0, 0.0),
+        1.0,
+        material3,
+    )));
+
+    Scene {
+        world,
+        lights: HittableList::new(),
+        camera: CameraSettings {
+            lookfrom: Point3::new(13.0, 2.0, 3.0),
+            lookat: Point3::new(0.0, 0.0, 0.0),
+            vup: Vec3::new(0.0, 1.0, 0.0),
+            vfov: 20.0,
+            aperture: 0.1,
+            focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: if bouncing { 1.0 } else { 0.0 },
+        },
+        environment: Arc::new(GradientEnvironment::sky()),
+        settings: RenderSettings::default(),
+    }
+}
+
+/// The Cornell box: a closed room lit only by the area light in its ceiling.
+pub fn cornell_box() -> Scene {
+    let white: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(Color::new(15.0, 15.0, 15.0)));
+    let light: Arc<dyn Hittable> =
+        Arc::new(Quad::xz_rect(213.0, 343.0, 227.0, 332.0, 554.0, light));
+    let mut world: HittableList = cornell_room(light.clone(), white.clone());
+    let (tall_box, short_box) = cornell_boxes(white);
+    world.add(tall_box);
+    world.add(short_box);
+    cornell_scene(world, light)
+}
+
+/// The Cornell box with its two blocks replaced by smoke and fog, lit by a
+/// larger, dimmer ceiling light.
+pub fn cornell_smoke() -> Scene {
+    let white: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(Color::new(7.0, 7.0, 7.0)));
+    let light: Arc<dyn Hittable> =
+        Arc::new(Quad::xz_rect(113.0, 443.0, 127.0, 432.0, 554.0, light));
+    let mut world: HittableList = cornell_room(light.clone(), white.clone());
+    let (tall_box, short_box) = cornell_boxes(white);
+    world.add(Arc::new(ConstantMedium::new(
+        tall_box,
+        0.01,
+        Color::new(0.0, 0.0, 0.0),
+    )));
+    world.add(Arc::new(ConstantMedium::new(
+        short_box,
+        0.01,
+        Color::new(1.0, 1.0, 1.0),
+    )));
+    cornell_scene(world, light)
+}
+
+/// The five walls of the box with `light` set into the ceiling.
+fn cornell_room(light: Arc<dyn Hittable>, white: Arc<dyn Material>) -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let red: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.65, 0.05, 0.05)));
+    let green: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.12, 0.45, 0.15)));
+
+    world.add(Arc::new(Quad::yz_rect(
+        0.0, 555.0, 0.0, 555.0, 555.0, green,
+    )));
+    world.add(Arc::new(Quad::yz_rect(0.0, 555.0, 0.0, 555.0, 0.0, red)));
+    world.add(light);
+    world.add(Arc::new(Quad::xz_rect(
+        0.0,
+        555.0,
+        0.0,
+        555.0,
+        0.0,
+        white.clone(),
+    )));
+    world.add(Arc::new(Quad::xz_rect(
+        0.0,
+        555.0,
+        0.0,
+        555.0,
+        555.0,
+        white.clone(),
+    )));
+    world.add(Arc::new(Quad::xy_rect(
+        0.0, 555.0, 0.0, 555.0, 555.0, white,
+    )));
+    world
+}
+
+/// The tall and the short block, each turned slightly about its corner.
+fn cornell_boxes(white: Arc<dyn Material>) -> (Arc<dyn Hittable>, Arc<dyn Hittable>) {
+    let tall_box: Arc<dyn Hittable> = Arc::new(BoxShape::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(165.0, 330.0, 165.0),
+        white.clone(),
+    ));
+    let short_box: Arc<dyn Hittable> = Arc::new(BoxShape::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(165.0, 165.0, 165.0),
+        white,
+    ));
+    (
+        Arc::new(Instance::new(
+            tall_box,
+            Transform::translate(Vec3::new(265.0, 0.0, 295.0)) * Transform::rotate_y(15.0),
+        )),
+        Arc::new(Instance::new(
+            short_box,
+            Transform::translate(Vec3::new(130.0, 0.0, 65.0)) * Transform::rotate_y(-18.0),
+        )),
+    )
+}
+
+/// The room's camera and settings, sampling `light` directly.
+fn cornell_scene(world: HittableList, light: Arc<dyn Hittable>) -> Scene {
+    let mut lights: HittableList = HittableList::new();
+    lights.add(light);
+    Scene {
+        world,
+        lights,
+        camera: CameraSettings {
+            lookfrom: Point3::new(278.0, 278.0, -800.0),
+            lookat: Point3::new(278.0, 278.0, 0.0),
+            vup: Vec3::new(0.0, 1.0, 0.0),
+            vfov: 40.0,
+            aperture: 0.0,
+            focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+        },
+        environment: Arc::new(ConstantEnvironment::new(Color::new(0.0, 0.0, 0.0))),
+        settings: RenderSettings {
+            width: 400,
+            height: 400,
+            samples_per_pixel: 200,
+            ..RenderSettings::default()
+        },
+    }
+}
@@ -0,0 +1,1371 @@
+use crate::camera::*;
+use crate::environment::*;
+use crate::loader::*;
+use crate::material::*;
+use crate::object::*;
+use crate::ray::{MisHeuristic, PathSettings};
+use crate::render::AdaptiveSettings;
+use crate::sampler::SamplerKind;
+use crate::texture::*;
+use crate::vec3::*;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderSettings {
+    pub width: usize,
+    pub height: usize,
+    pub samples_per_pixel: u16,
+    /// Whether noisy pixels take extra samples, and how many.
+    pub adaptive: Option<AdaptiveSettings>,
+    pub max_depth: u16,
+    pub min_bounces: u16,
+    pub mis: MisHeuristic,
+    pub sampler: SamplerKind,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 384,
+            height: 216,
+            samples_per_pixel: 100,
+            adaptive: None,
+            max_depth: PathSettings::default().max_depth,
+            min_bounces: PathSettings::default().min_bounces,
+            mis: MisHeuristic::default(),
+            sampler: SamplerKind::default(),
+        }
+    }
+}
+
+impl RenderSettings {
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    pub fn path(&self) -> PathSettings {
+        PathSettings {
+            max_depth: self.max_depth,
+            min_bounces: self.min_bounces,
+            heuristic: self.mis,
+        }
+    }
+}
+
+/// The inputs of `Camera::new` minus the aspect ratio, which follows the
+/// image size and may be overridden after the scene is loaded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraSettings {
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    pub vup: Vec3,
+    pub vfov: f32,
+    pub aperture: f32,
+    pub focus_dist: f32,
+    pub shutter_open: f32,
+    pub shutter_close: f32,
+}
+
+impl CameraSettings {
+    pub fn camera(&self, aspect_ratio: f32) -> Camera {
+        Camera::new(
+            self.lookfrom,
+            self.lookat,
+            self.vup,
+            self.vfov,
+            aspect_ratio,
+            self.aperture,
+            self.focus_dist,
+            self.shutter_open,
+            self.shutter_close,
+        )
+    }
+}
+
+pub struct Scene {
+    pub world: HittableList,
+    /// The emitters in `world` that are sampled directly.
+    pub lights: HittableList,
+    pub camera: CameraSettings,
+    /// What rays that leave the scene see, from the `background` field.
+    pub environment: Arc<dyn Environment>,
+    pub settings: RenderSettings,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(PathBuf, std::io::Error),
+    Syntax(PathBuf, serde_json::Error),
+    /// The document is valid JSON but does not describe a scene; `path` points
+    /// at the offending value, e.g. `objects[2].material`.
+    Invalid {
+        path: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(file, e) => write!(f, "{}: {}", file.display(), e),
+            SceneError::Syntax(file, e) => write!(f, "{}: {}", file.display(), e),
+            SceneError::Invalid { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+/// Loads a JSON scene description. Relative asset paths, such as mesh files,
+/// are resolved against the directory containing the scene file.
+pub fn load_scene(path: &Path) -> Result<Scene, SceneError> {
+    let source: String =
+        std::fs::read_to_string(path).map_err(|e| SceneError::Io(path.to_path_buf(), e))?;
+    let document: Value =
+        serde_json::from_str(&source).map_err(|e| SceneError::Syntax(path.to_path_buf(), e))?;
+    parse_scene(&document, path.parent().unwrap_or_else(|| Path::new("")))
+}
+
+pub fn parse_scene(document: &Value, base_dir: &Path) -> Result<Scene, SceneError> {
+    let root: Node = Node::root(document);
+    root.check_fields(&["render", "camera", "background", "materials", "objects"])?;
+
+    let settings: RenderSettings = match root.opt("render") {
+        Some(node) => parse_render(&node)?,
+        None => RenderSettings::default(),
+    };
+    let camera: CameraSettings = parse_camera(&root.get("camera")?)?;
+    let environment: Arc<dyn Environment> = match root.opt("background") {
+        Some(node) => parse_background(&node, base_dir)?,
+        None => Arc::new(GradientEnvironment::sky()),
+    };
+
+    let mut materials: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    if let Some(node) = root.opt("materials") {
+        for (name, material) in node.as_object()? {
+            materials.insert(name.to_string(), parse_material(&material, base_dir)?);
+        }
+    }
+
+    let mut world: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+    let objects: Node = root.get("objects")?;
+    for object in objects.as_array()? {
+        parse_object(&object, &materials, None, base_dir, &mut world, &mut lights)?;
+    }
+    if world.is_empty() {
+        return objects.error("the scene has nothing to render".to_string());
+    }
+
+    Ok(Scene {
+        world,
+        lights,
+        camera,
+        environment,
+        settings,
+    })
+}
+
+fn parse_render(node: &Node) -> Result<RenderSettings, SceneError> {
+    node.check_fields(&[
+        "width",
+        "height",
+        "samples_per_pixel",
+        "adaptive",
+        "max_depth",
+        "min_bounces",
+        "mis",
+        "sampler",
+    ])?;
+    let defaults: RenderSettings = RenderSettings::default();
+    let positive = |key: &str, default: usize| -> Result<usize, SceneError> {
+        match node.opt(key) {
+            Some(n) => match n.as_usize()? {
+                0 => n.error("must be at least 1".to_string()),
+                x => Ok(x),
+            },
+            None => Ok(default),
+        }
+    };
+    let samples_per_pixel: u16 = match node.opt("samples_per_pixel") {
+        Some(n) => match n.as_u16()? {
+            0 => return n.error("must be at least 1".to_string()),
+            x => x,
+        },
+        None => defaults.samples_per_pixel,
+    };
+    let adaptive: Option<AdaptiveSettings> = match node.opt("adaptive") {
+        Some(n) => Some(parse_adaptive(&n, samples_per_pixel)?),
+        None => defaults.adaptive,
+    };
+    let mis: MisHeuristic = match node.opt("mis") {
+        Some(n) => match n.as_str()? {
+            "balance" => MisHeuristic::Balance,
+            "power" => MisHeuristic::Power,
+            other => {
+                return n.error(format!(
+                    "unknown heuristic '{}' (expected balance or power)",
+                    other
+                ))
+            }
+        },
+        None => defaults.mis,
+    };
+    let sampler: SamplerKind = match node.opt("sampler") {
+        Some(n) => match n.as_str()? {
+            "independent" => SamplerKind::Independent,
+            "stratified" => SamplerKind::Stratified,
+            "halton" => SamplerKind::Halton,
+            "sobol" => SamplerKind::Sobol,
+            other => {
+                return n.error(format!(
+                    "unknown sampler '{}' (expected independent, stratified, halton or sobol)",
+                    other
+                ))
+            }
+        },
+        None => defaults.sampler,
+    };
+    Ok(RenderSettings {
+        width: positive("width", defaults.width)?,
+        height: positive("height", defaults.height)?,
+        samples_per_pixel,
+        adaptive,
+        max_depth: node
+            .opt("max_depth")
+            .map_or(Ok(defaults.max_depth), |n| n.as_u16())?,
+        min_bounces: node
+            .opt("min_bounces")
+            .map_or(Ok(defaults.min_bounces), |n| n.as_u16())?,
+        mis,
+        sampler,
+    })
+}
+
+fn parse_adaptive(node: &Node, samples_per_pixel: u16) -> Result<AdaptiveSettings, SceneError> {
+    node.check_fields(&["threshold", "max_samples_per_pixel"])?;
+    let threshold: Node = node.get("threshold")?;
+    let value: f32 = threshold.as_f32()?;
+    if !value.is_finite() || value <= 0.0 {
+        return threshold.error("must be a positive number".to_string());
+    }
+    let mut adaptive: AdaptiveSettings = AdaptiveSettings::new(value, samples_per_pixel);
+    if let Some(max) = node.opt("max_samples_per_pixel") {
+        adaptive.max_samples = max.as_u16()?;
+        if adaptive.max_samples < samples_per_pixel {
+            return max.error(format!(
+                "must be at least samples_per_pixel ({})",
+                samples_per_pixel
+            ));
+        }
+    }
+    Ok(adaptive)
+}
+
+fn parse_camera(node: &Node) -> Result<CameraSettings, SceneError> {
+    node.check_fields(&[
+        "lookfrom",
+        "lookat",
+        "vup",
+        "vfov",
+        "aperture",
+        "focus_dist",
+        "shutter_open",
+        "shutter_close",
+    ])?;
+    let lookfrom: Point3 = node.get("lookfrom")?.as_vec3()?;
+    let lookat: Point3 = node.get("lookat")?.as_vec3()?;
+    let vup: Vec3 = node.vec3_or("vup", Vec3::new(0.0, 1.0, 0.0))?;
+    if (lookfrom - lookat).length_squared() == 0.0 {
+        return node.error("'lookfrom' and 'lookat' must differ".to_string());
+    }
+
+    let vfov_node: Node = node.get("vfov")?;
+    let vfov: f32 = vfov_node.as_f32()?;
+    if vfov <= 0.0 || vfov >= 180.0 {
+        return vfov_node.error("must be between 0 and 180 degrees".to_string());
+    }
+
+    let shutter_open: f32 = node.f32_or("shutter_open", 0.0)?;
+    let shutter_close: f32 = node.f32_or("shutter_close", shutter_open)?;
+    if shutter_close < shutter_open {
+        return node
+            .get("shutter_close")?
+            .error("must not be before 'shutter_open'".to_string());
+    }
+
+    Ok(CameraSettings {
+        lookfrom,
+        lookat,
+        vup,
+        vfov,
+        aperture: node.f32_or("aperture", 0.0)?,
+        focus_dist: node.f32_or("focus_dist", (lookfrom - lookat).length())?,
+        shutter_open,
+        shutter_close,
+    })
+}
+
+/// Either `"sky"`, a constant color such as `[0, 0, 0]`, or an object with a
+/// `type` of `gradient`, `equirect` or `physical_sky`.
+fn parse_background(node: &Node, base_dir: &Path) -> Result<Arc<dyn Environment>, SceneError> {
+    if let Ok(name) = node.as_str() {
+        return match name {
+            "sky" => Ok(Arc::new(GradientEnvironment::sky())),
+            other => node.error(format!(
+                "unknown background '{}' (expected \"sky\", a color or an object)",
+                other
+            )),
+        };
+    }
+    if node.as_array().is_ok() {
+        return Ok(Arc::new(ConstantEnvironment::new(node.as_vec3()?)));
+    }
+    let kind: Node = node.get("type")?;
+    match kind.as_str()? {
+        "gradient" => {
+            node.check_fields(&["type", "bottom", "top"])?;
+            let sky: GradientEnvironment = GradientEnvironment::sky();
+            let white: Vec3 = sky.color(&Vec3::new(0.0, -1.0, 0.0));
+            let blue: Vec3 = sky.color(&Vec3::new(0.0, 1.0, 0.0));
+            Ok(Arc::new(GradientEnvironment::new(
+                node.vec3_or("bottom", white)?,
+                node.vec3_or("top", blue)?,
+            )))
+        }
+        "equirect" => {
+            node.check_fields(&["type", "file", "rotation", "intensity"])?;
+            let intensity: f32 = parse_intensity(node)?;
+            let file: Node = node.get("file")?;
+            let path: PathBuf = base_dir.join(file.as_str()?);
+            match EnvironmentMap::load(&path, node.f32_or("rotation", 0.0)?, intensity) {
+                Ok(map) => Ok(Arc::new(map)),
+                Err(e) => file.error(format!("{}: {}", path.display(), e)),
+            }
+        }
+        "physical_sky" => {
+            node.check_fields(&[
+                "type",
+                "elevation",
+                "azimuth",
+                "turbidity",
+                "sun_radius",
+                "intensity",
+            ])?;
+            let in_range = |key: &str, default: f32, min: f32, max: f32| {
+                let value: f32 = node.f32_or(key, default)?;
+                if value < min || value > max {
+                    return node
+                        .get(key)?
+                        .error(format!("must lie between {} and {}", min, max));
+                }
+                Ok(value)
+            };
+            let elevation: f32 = in_range("elevation", 45.0, 0.0, 90.0)?;
+            let turbidity: f32 = in_range("turbidity", 3.0, 2.0, 10.0)?;
+            let sun_radius: f32 = in_range("sun_radius", SUN_RADIUS, 0.01, 45.0)?;
+            let intensity: f32 = parse_intensity(node)?;
+            Ok(Arc::new(
+                PhysicalSky::new(elevation, node.f32_or("azimuth", 0.0)?, turbidity)
+                    .with_sun_radius(sun_radius)
+                    .with_intensity(intensity),
+            ))
+        }
+        other => kind.error(format!(
+            "unknown background type '{}' (expected gradient, equirect or physical_sky)",
+            other
+        )),
+    }
+}
+
+/// An optional non-negative `intensity`, 1 by default.
+fn parse_intensity(node: &Node) -> Result<f32, SceneError> {
+    match node.opt("intensity") {
+        None => Ok(1.0),
+        Some(n) if n.as_f32()? < 0.0 => n.error("must not be negative".to_string()),
+        Some(n) => n.as_f32(),
+    }
+}
+
+fn parse_material(node: &Node, base_dir: &Path) -> Result<Arc<dyn Material>, SceneError> {
+    let kind: Node = node.get("type")?;
+    match kind.as_str()? {
+        "lambertian" => {
+            node.check_fields(&["type", "albedo"])?;
+            Ok(Arc::new(Lambertian::from_texture(parse_texture(
+                &node.get("albedo")?,
+                base_dir,
+            )?)))
+        }
+        "metal" => {
+            node.check_fields(&["type", "albedo", "fuzz"])?;
+            Ok(Arc::new(Metal::from_texture(
+                parse_texture(&node.get("albedo")?, base_dir)?,
+                node.f32_or("fuzz", 0.0)?,
+            )))
+        }
+        "dielectric" => {
+            node.check_fields(&["type", "ref_idx"])?;
+            Ok(Arc::new(Dielectric::new(node.get("ref_idx")?.as_f32()?)))
+        }
+        "isotropic" => {
+            node.check_fields(&["type", "albedo"])?;
+            Ok(Arc::new(Isotropic::from_texture(parse_texture(
+                &node.get("albedo")?,
+                base_dir,
+            )?)))
+        }
+        "diffuse_light" => {
+            node.check_fields(&["type", "emit"])?;
+            Ok(Arc::new(DiffuseLight::new(node.get("emit")?.as_vec3()?)))
+        }
+        other => kind.error(format!(
+            "unknown material type '{}' (expected lambertian, metal, dielectric, isotropic or diffuse_light)",
+            other
+        )),
+    }
+}
+
+/// A plain color, or a texture object such as a `checker`, an `image` or one
+/// of the noise patterns.
+fn parse_texture(node: &Node, base_dir: &Path) -> Result<Arc<dyn Texture>, SceneError> {
+    if node.as_array().is_ok() {
+        return Ok(Arc::new(SolidColor::new(node.as_vec3()?)));
+    }
+    let kind: Node = node.get("type")?;
+    match kind.as_str()? {
+        "checker" => {
+            node.check_fields(&["type", "scale", "even", "odd"])?;
+            let scale: Node = node.get("scale")?;
+            if scale.as_f32()? <= 0.0 {
+                return scale.error("must be positive".to_string());
+            }
+            Ok(Arc::new(Checker::new(
+                scale.as_f32()?,
+                parse_texture(&node.get("even")?, base_dir)?,
+                parse_texture(&node.get("odd")?, base_dir)?,
+            )))
+        }
+        "image" => {
+            node.check_fields(&["type", "file", "wrap"])?;
+            let wrap: WrapMode = match node.opt("wrap") {
+                None => WrapMode::default(),
+                Some(n) => match n.as_str()? {
+                    "repeat" => WrapMode::Repeat,
+                    "mirror" => WrapMode::Mirror,
+                    "clamp" => WrapMode::Clamp,
+                    other => {
+                        return n.error(format!(
+                            "unknown wrap mode '{}' (expected repeat, mirror or clamp)",
+                            other
+                        ))
+                    }
+                },
+            };
+            let file: Node = node.get("file")?;
+            let path: PathBuf = base_dir.join(file.as_str()?);
+            match ImageTexture::load(&path, wrap) {
+                Ok(texture) => Ok(Arc::new(texture)),
+                Err(e) => file.error(format!("{}: {}", path.display(), e)),
+            }
+        }
+        name @ ("turbulence" | "marble" | "wood" | "cloud") => {
+            node.check_fields(&["type", "scale", "octaves", "seed", "low", "high"])?;
+            let pattern: NoisePattern = match name {
+                "turbulence" => NoisePattern::Turbulence,
+                "marble" => NoisePattern::Marble,
+                "wood" => NoisePattern::Wood,
+                _ => NoisePattern::Cloud,
+            };
+            let (seed, octaves) = parse_noise_parameters(node)?;
+            let mut texture: NoiseTexture =
+                NoiseTexture::new(pattern, seed, node.f32_or("scale", 1.0)?, octaves);
+            if node.opt("low").is_some() || node.opt("high").is_some() {
+                texture = texture.with_colors(
+                    node.get("low")?.as_vec3()?,
+                    node.get("high")?.as_vec3()?,
+                );
+            }
+            Ok(Arc::new(texture))
+        }
+        other => kind.error(format!(
+            "unknown texture type '{}' (expected checker, image, turbulence, marble, wood or cloud)",
+            other
+        )),
+    }
+}
+
+/// The optional `seed` and `octaves` of procedural noise.
+fn parse_noise_parameters(node: &Node) -> Result<(u64, u32), SceneError> {
+    let octaves: u32 = match node.opt("octaves") {
+        Some(n) => match n.as_u16()? {
+            0 => return n.error("must be at least 1".to_string()),
+            x => x as u32,
+        },
+        None => 7,
+    };
+    let seed: u64 = node.opt("seed").map_or(Ok(0), |n| n.as_usize())? as u64;
+    Ok((seed, octaves))
+}
+
+/// A voxel grid read from a raw float file, `{"type": "raw", "file":
+/// "smoke.raw", "resolution": [64, 64, 64]}`, or filled with noise,
+/// `{"type": "noise", "resolution": [64, 64, 64], "scale": 3}`.
+fn parse_density_grid(node: &Node, base_dir: &Path) -> Result<DensityGrid, SceneError> {
+    let resolution_node: Node = node.get("resolution")?;
+    let axes: Vec<Node> = resolution_node.as_array()?;
+    if axes.len() != 3 {
+        return resolution_node.error(format!("expected 3 integers, found {}", axes.len()));
+    }
+    let mut resolution: [usize; 3] = [0; 3];
+    for (n, axis) in resolution.iter_mut().zip(axes) {
+        *n = axis.as_usize()?;
+        if *n == 0 {
+            return axis.error("must be at least 1".to_string());
+        }
+    }
+
+    let kind: Node = node.get("type")?;
+    match kind.as_str()? {
+        "raw" => {
+            node.check_fields(&["type", "file", "resolution"])?;
+            let file: Node = node.get("file")?;
+            DensityGrid::load_raw(&base_dir.join(file.as_str()?), resolution)
+                .or_else(|e| file.error(e.to_string()))
+        }
+        "noise" => {
+            node.check_fields(&["type", "resolution", "scale", "octaves", "seed"])?;
+            let (seed, octaves) = parse_noise_parameters(node)?;
+            Ok(DensityGrid::from_noise(
+                resolution,
+                seed,
+                node.f32_or("scale", 1.0)?,
+                octaves,
+            ))
+        }
+        other => kind.error(format!(
+            "unknown grid type '{}' (expected raw or noise)",
+            other
+        )),
+    }
+}
+
+/// Adds the objects `node` describes to `world`, and also to `lights` if it
+/// is marked `"light": true`. `fallback` is the material of objects that name
+/// none, which only the boundary of a medium may omit.
+fn parse_object(
+    node: &Node,
+    materials: &HashMap<String, Arc<dyn Material>>,
+    fallback: Option<&Arc<dyn Material>>,
+    base_dir: &Path,
+    world: &mut HittableList,
+    lights: &mut HittableList,
+) -> Result<(), SceneError> {
+    let light: bool = match node.opt("light") {
+        Some(n) => {
+            // Only the untransformed shapes themselves know how to be sampled.
+            if n.as_bool()? && (node.opt("transform").is_some() || node.opt("keyframes").is_some())
+            {
+                return n.error("a light cannot be transformed or animated".to_string());
+            }
+            n.as_bool()?
+        }
+        None => false,
+    };
+    let material = || -> Result<Arc<dyn Material>, SceneError> {
+        if let (None, Some(m)) = (node.opt("material"), fallback) {
+            return Ok(m.clone());
+        }
+        let name: Node = node.get("material")?;
+        match materials.get(name.as_str()?) {
+            Some(m) => Ok(m.clone()),
+            None => name.error(format!("unknown material '{}'", name.as_str()?)),
+        }
+    };
+
+    let mut objects: HittableList = HittableList::new();
+    let kind: Node = node.get("type")?;
+    match kind.as_str()? {
+        "sphere" => {
+            node.check_fields(&[
+                "type",
+                "transform",
+                "keyframes",
+                "center",
+                "radius",
+                "material",
+                "light",
+            ])?;
+            let radius: Node = node.get("radius")?;
+            if radius.as_f32()? <= 0.0 {
+                return radius.error("must be positive".to_string());
+            }
+            objects.add(Arc::new(Sphere::new(
+                node.get("center")?.as_vec3()?,
+                radius.as_f32()?,
+                material()?,
+            )));
+        }
+        "triangle" => {
+            node.check_fields(&["type", "transform", "keyframes", "vertices", "material"])?;
+            let vertices: Node = node.get("vertices")?;
+            let positions: Vec<Point3> = vertices
+                .as_array()?
+                .iter()
+                .map(|v| v.as_vec3())
+                .collect::<Result<_, _>>()?;
+            if positions.len() != 3 {
+                return vertices.error(format!("expected 3 vertices, found {}", positions.len()));
+            }
+            let mesh: TriangleMesh = TriangleMesh::new(positions, vec![[0, 1, 2]], material()?);
+            objects.add(Arc::new(Triangle::new(Arc::new(mesh), 0)));
+        }
+        "mesh" => {
+            node.check_fields(&["type", "transform", "keyframes", "file", "material"])?;
+            let file: Node = node.get("file")?;
+            let meshes: Vec<TriangleMesh> = load_obj(&base_dir.join(file.as_str()?), material()?)
+                .or_else(|e| file.error(e.to_string()))?;
+            if meshes.is_empty() {
+                return file.error("contains no faces".to_string());
+            }
+            for mesh in meshes {
+                objects.add(Arc::new(BvhNode::new(mesh.into_triangles())));
+            }
+        }
+        "moving_sphere" => {
+            node.check_fields(&[
+                "type",
+                "transform",
+                "keyframes",
+                "center0",
+                "center1",
+                "time0",
+                "time1",
+                "radius",
+                "material",
+            ])?;
+            let radius: Node = node.get("radius")?;
+            if radius.as_f32()? <= 0.0 {
+                return radius.error("must be positive".to_string());
+            }
+            objects.add(Arc::new(MovingSphere::new(
+                node.get("center0")?.as_vec3()?,
+                node.get("center1")?.as_vec3()?,
+                node.f32_or("time0", 0.0)?,
+                node.f32_or("time1", 1.0)?,
+                radius.as_f32()?,
+                material()?,
+            )));
+        }
+        "constant_medium" => {
+            node.check_fields(&[
+                "type",
+                "transform",
+                "keyframes",
+                "boundary",
+                "density",
+                "albedo",
+            ])?;
+            let density: Node = node.get("density")?;
+            if density.as_f32()? <= 0.0 {
+                return density.error("must be positive".to_string());
+            }
+            let phase_function: Arc<dyn Material> = Arc::new(Isotropic::from_texture(
+                parse_texture(&node.get("albedo")?, base_dir)?,
+            ));
+            objects.add(Arc::new(ConstantMedium::with_phase_function(
+                parse_boundary(node, materials, &phase_function, base_dir)?,
+                density.as_f32()?,
+                phase_function,
+            )));
+        }
+        "heterogeneous_medium" => {
+            node.check_fields(&[
+                "type",
+                "transform",
+                "keyframes",
+                "boundary",
+                "grid",
+                "absorption",
+                "scattering",
+                "emission",
+                "albedo",
+                "g",
+            ])?;
+            let coefficient = |key: &str, default: f32| -> Result<f32, SceneError> {
+                let c: f32 = node.f32_or(key, default)?;
+                if c < 0.0 {
+                    return node.get(key)?.error("must not be negative".to_string());
+                }
+                Ok(c)
+            };
+            let absorption: f32 = coefficient("absorption", 0.0)?;
+            let scattering: f32 = coefficient("scattering", 1.0)?;
+            let g: f32 = node.f32_or("g", 0.0)?;
+            if g <= -1.0 || g >= 1.0 {
+                return node.get("g")?.error("must lie between -1 and 1".to_string());
+            }
+            let albedo: Arc<dyn Texture> = match node.opt("albedo") {
+                Some(n) => parse_texture(&n, base_dir)?,
+                None => Arc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0))),
+            };
+            let phase_function: Arc<dyn Material> =
+                Arc::new(HenyeyGreenstein::from_texture(albedo, g));
+            let mut medium: HeterogeneousMedium = HeterogeneousMedium::new(
+                parse_boundary(node, materials, &phase_function, base_dir)?,
+                Arc::new(parse_density_grid(&node.get("grid")?, base_dir)?),
+                absorption,
+                scattering,
+                phase_function,
+            );
+            if let Some(emission) = node.opt("emission") {
+                if absorption == 0.0 {
+                    return emission.error("only an absorbing medium can emit".to_string());
+                }
+                medium = medium.with_emission(emission.as_vec3()?);
+            }
+            objects.add(Arc::new(medium));
+        }
+        "quad" => {
+            node.check_fields(&[
+                "type",
+                "transform",
+                "keyframes",
+                "q",
+                "u",
+                "v",
+                "material",
+                "light",
+            ])?;
+            let (u, v) = (node.get("u")?.as_vec3()?, node.get("v")?.as_vec3()?);
+            if u.cross(&v).length_squared() == 0.0 {
+                return node.error("edges 'u' and 'v' must not be parallel".to_string());
+            }
+            objects.add(Arc::new(Quad::new(
+                node.get("q")?.as_vec3()?,
+                u,
+                v,
+                material()?,
+            )));
+        }
+        rect @ ("xy_rect" | "xz_rect" | "yz_rect") => {
+            // The two in-plane axes, then the one the rect is perpendicular to.
+            let (a, b, k) = match rect {
+                "xy_rect" => ("x", "y", "z"),
+                "xz_rect" => ("x", "z", "y"),
+                _ => ("y", "z", "x"),
+            };
+            node.check_fields(&[
+                "type",
+                "transform",
+                "keyframes",
+                a,
+                b,
+                k,
+                "material",
+                "light",
+            ])?;
+            let range = |key: &str| -> Result<(f32, f32), SceneError> {
+                let n: Node = node.get(key)?;
+                let items: Vec<Node> = n.as_array()?;
+                if items.len() != 2 {
+                    return n.error(format!("expected 2 numbers, found {}", items.len()));
+                }
+                let (lo, hi) = (items[0].as_f32()?, items[1].as_f32()?);
+                if lo >= hi {
+                    return n.error("the range must not be empty".to_string());
+                }
+                Ok((lo, hi))
+            };
+            let ((a0, a1), (b0, b1)) = (range(a)?, range(b)?);
+            let k: f32 = node.get(k)?.as_f32()?;
+            let quad: Quad = match rect {
+                "xy_rect" => Quad::xy_rect(a0, a1, b0, b1, k, material()?),
+                "xz_rect" => Quad::xz_rect(a0, a1, b0, b1, k, material()?),
+                _ => Quad::yz_rect(a0, a1, b0, b1, k, material()?),
+            };
+            objects.add(Arc::new(quad));
+        }
+        "plane" => {
+            node.check_fields(&["type", "transform", "keyframes", "point", "normal", "material"])?;
+            let normal: Node = node.get("normal")?;
+            if normal.as_vec3()?.length_squared() == 0.0 {
+                return normal.error("must not be zero".to_string());
+            }
+            objects.add(Arc::new(Plane::new(
+                node.get("point")?.as_vec3()?,
+                normal.as_vec3()?,
+                material()?,
+            )));
+        }
+        "box" => {
+            node.check_fields(&["type", "transform", "keyframes", "min", "max", "material"])?;
+            let (min, max) = (node.get("min")?.as_vec3()?, node.get("max")?.as_vec3()?);
+            if (0..3).any(|axis| min[axis] == max[axis]) {
+                return node.error("'min' and 'max' must differ on every axis".to_string());
+            }
+            objects.add(Arc::new(BoxShape::new(min, max, material()?)));
+        }
+        other => {
+            return kind.error(format!(
+                "unknown object type '{}' (expected sphere, moving_sphere, triangle, mesh, quad, xy_rect, xz_rect, yz_rect, plane, box, constant_medium or heterogeneous_medium)",
+                other
+            ))
+        }
+    }
+
+    if node.opt("transform").is_none() && node.opt("keyframes").is_none() {
+        for object in objects.into_objects() {
+            if light {
+                lights.add(object.clone());
+            }
+            world.add(object);
+        }
+        return Ok(());
+    }
+
+    let mut parts: Vec<Arc<dyn Hittable>> = objects.into_objects();
+    let mut object: Arc<dyn Hittable> = if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        let mut list: HittableList = HittableList::new();
+        for part in parts {
+            list.add(part);
+        }
+        Arc::new(BvhNode::new(list))
+    };
+    // A static transform poses the object before any animation moves it.
+    if let Some(n) = node.opt("transform") {
+        object = Arc::new(Instance::new(object, parse_transform(&n)?));
+    }
+    if let Some(n) = node.opt("keyframes") {
+        object = Arc::new(KeyframedInstance::new(object, parse_keyframes(&n)?));
+    }
+    world.add(object);
+    Ok(())
+}
+
+/// The closed shape a medium fills. It only shapes the medium and is never
+/// shaded itself, so it may leave out its material.
+fn parse_boundary(
+    node: &Node,
+    materials: &HashMap<String, Arc<dyn Material>>,
+    phase_function: &Arc<dyn Material>,
+    base_dir: &Path,
+) -> Result<Arc<dyn Hittable>, SceneError> {
+    let boundary_node: Node = node.get("boundary")?;
+    let mut boundary: HittableList = HittableList::new();
+    let mut lights: HittableList = HittableList::new();
+    parse_object(
+        &boundary_node,
+        materials,
+        Some(phase_function),
+        base_dir,
+        &mut boundary,
+        &mut lights,
+    )?;
+    if !lights.is_empty() {
+        return boundary_node.error("the boundary of a medium cannot be a light".to_string());
+    }
+    if boundary.bounding_box().is_none() {
+        return boundary_node.error("the boundary must be a closed shape".to_string());
+    }
+    Ok(Arc::new(BvhNode::new(boundary)))
+}
+
+/// Poses over time, e.g. `[{"time": 0, "translate": [0, 0, 0]}, {"time": 1,
+/// "translate": [0, 1, 0], "rotate": {"axis": [0, 1, 0], "angle": 90}}]`.
+fn parse_keyframes(node: &Node) -> Result<Vec<Keyframe>, SceneError> {
+    let steps: Vec<Node> = node.as_array()?;
+    if steps.is_empty() {
+        return node.error("expected at least one keyframe".to_string());
+    }
+    let mut keyframes: Vec<Keyframe> = Vec::new();
+    for step in steps {
+        step.check_fields(&["time", "translate", "rotate", "scale"])?;
+        let mut keyframe: Keyframe = Keyframe::new(
+            step.get("time")?.as_f32()?,
+            step.vec3_or("translate", Vec3::new(0.0, 0.0, 0.0))?,
+        );
+        if let Some(rotate) = step.opt("rotate") {
+            rotate.check_fields(&["axis", "angle"])?;
+            let axis: Node = rotate.get("axis")?;
+            if axis.as_vec3()?.length_squared() == 0.0 {
+                return axis.error("must not be zero".to_string());
+            }
+            keyframe = keyframe.with_rotation(axis.as_vec3()?, rotate.get("angle")?.as_f32()?);
+        }
+        if let Some(scale) = step.opt("scale") {
+            let factors: Vec3 = match scale.as_f32() {
+                Ok(s) => Vec3::new(s, s, s),
+                Err(_) => scale.as_vec3()?,
+            };
+            if factors.x() == 0.0 || factors.y() == 0.0 || factors.z() == 0.0 {
+                return scale.error("scale factors must not be zero".to_string());
+            }
+            keyframe = keyframe.with_scale(factors);
+        }
+        keyframes.push(keyframe);
+    }
+    Ok(keyframes)
+}
+
+/// A list of steps applied in order, e.g. `[{"scale": 2}, {"rotate_y": 30},
+/// {"translate": [1, 0, 0]}]` scales first and translates last.
+fn parse_transform(node: &Node) -> Result<Transform, SceneError> {
+    let mut transform: Transform = Transform::identity();
+    for step in node.as_array()? {
+        let fields: Vec<(&str, Node)> = step.as_object()?;
+        if fields.len() != 1 {
+            return step.error("expected exactly one transform per step".to_string());
+        }
+        let (name, value) = &fields[0];
+        let next: Transform = match *name {
+            "translate" => Transform::translate(value.as_vec3()?),
+            "scale" => {
+                let factors: Vec3 = match value.as_f32() {
+                    Ok(s) => Vec3::new(s, s, s),
+                    Err(_) => value.as_vec3()?,
+                };
+                if factors.x() == 0.0 || factors.y() == 0.0 || factors.z() == 0.0 {
+                    return value.error("scale factors must not be zero".to_string());
+                }
+                Transform::scale(factors)
+            }
+            "rotate_x" => Transform::rotate_x(value.as_f32()?),
+            "rotate_y" => Transform::rotate_y(value.as_f32()?),
+            "rotate_z" => Transform::rotate_z(value.as_f32()?),
+            "rotate" => {
+                value.check_fields(&["axis", "angle"])?;
+                let axis: Node = value.get("axis")?;
+                if axis.as_vec3()?.length_squared() == 0.0 {
+                    return axis.error("must not be zero".to_string());
+                }
+                Transform::rotate(axis.as_vec3()?, value.get("angle")?.as_f32()?)
+            }
+            "matrix" => {
+                let rows: Vec<Node> = value.as_array()?;
+                if rows.len() != 3 && rows.len() != 4 {
+                    return value.error(format!("expected 3 or 4 rows, found {}", rows.len()));
+                }
+                let mut m: [[f32; 4]; 4] = Transform::identity().matrix();
+                for (i, row) in rows.iter().enumerate() {
+                    let items: Vec<Node> = row.as_array()?;
+                    if items.len() != 4 {
+                        return row.error(format!("expected 4 numbers, found {}", items.len()));
+                    }
+                    for (j, item) in items.iter().enumerate() {
+                        m[i][j] = item.as_f32()?;
+                    }
+                }
+                match Transform::from_matrix(m) {
+                    Some(t) => t,
+                    None => {
+                        return value.error(
+                            "the matrix must be affine (last row 0 0 0 1) and invertible"
+                                .to_string(),
+                        )
+                    }
+                }
+            }
+            other => {
+                return step.error(format!(
+                    "unknown transform '{}' (expected translate, scale, rotate_x, rotate_y, rotate_z, rotate or matrix)",
+                    other
+                ))
+            }
+        };
+        transform = next * transform;
+    }
+    Ok(transform)
+}
+
+pub mod builtin;
+pub mod node;
+
+pub use builtin::{builtin_scene, random_scene, BUILTIN_SCENES};
+pub use node::Node;
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error_of(document: Value) -> String {
+        match parse_scene(&document, Path::new("")) {
+            Ok(_) => panic!("scene should not load"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn minimal() -> Value {
+        json!({
+            "render": { "width": 32, "height": 16, "samples_per_pixel": 4 },
+            "camera": { "lookfrom": [0, 0, 5], "lookat": [0, 0, 0], "vfov": 40 },
+            "materials": {
+                "red": { "type": "lambertian", "albedo": [0.8, 0.1, 0.1] },
+                "glass": { "type": "dielectric", "ref_idx": 1.5 }
+            },
+            "objects": [
+                { "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "red" },
+                { "type": "triangle", "vertices": [[0, 0, 0], [1, 0, 0], [0, 1, 0]], "material": "glass" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_parse_minimal() {
+        let scene: Scene = parse_scene(&minimal(), Path::new("")).unwrap();
+        assert_eq!(scene.settings.width, 32);
+        assert_eq!(scene.settings.height, 16);
+        assert_eq!(scene.settings.samples_per_pixel, 4);
+        assert_eq!(
+            scene.settings.max_depth,
+            RenderSettings::default().max_depth
+        );
+        assert!(scene.world.bounding_box().is_some());
+        let up: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+        assert_eq!(
+            scene.environment.color(&up),
+            GradientEnvironment::sky().color(&up)
+        );
+    }
+
+    #[test]
+    fn test_parse_light_and_background() {
+        let mut document: Value = minimal();
+        document["background"] = json!([0, 0, 0]);
+        document["materials"]["lamp"] = json!({ "type": "diffuse_light", "emit": [4, 4, 4] });
+        document["objects"][0]["material"] = json!("lamp");
+        let scene: Scene = parse_scene(&document, Path::new("")).unwrap();
+        assert_eq!(
+            scene.environment.color(&Vec3::new(0.0, 1.0, 0.0)),
+            Color::new(0.0, 0.0, 0.0)
+        );
+        assert!(scene.lights.is_empty());
+        document["objects"][0]["light"] = json!(true);
+        let scene: Scene = parse_scene(&document, Path::new("")).unwrap();
+        assert!(scene.lights.bounding_box().is_some());
+        document["objects"][0]["transform"] = json!([{ "translate": [0, 1, 0] }]);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[0].light: a light cannot be transformed or animated"
+        );
+        document["objects"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("transform");
+        document["objects"][1]["light"] = json!(true);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[1]: unknown field 'light'"
+        );
+        document["objects"][1]
+            .as_object_mut()
+            .unwrap()
+            .remove("light");
+
+        document["materials"]["red"]["albedo"] = json!({
+            "type": "checker",
+            "scale": 0.5,
+            "even": [1, 1, 1],
+            "odd": { "type": "checker", "scale": 0.1, "even": [0, 0, 0], "odd": [1, 0, 0] }
+        });
+        assert!(parse_scene(&document, Path::new("")).is_ok());
+
+        document["materials"]["red"]["albedo"] =
+            json!({ "type": "marble", "scale": 4, "octaves": 5, "seed": 9 });
+        assert!(parse_scene(&document, Path::new("")).is_ok());
+        document["materials"]["red"]["albedo"]["low"] = json!([0, 0, 0]);
+        assert_eq!(
+            error_of(document.clone()),
+            "materials.red.albedo: missing field 'high'"
+        );
+        document["materials"]["red"]["albedo"] = json!([0.8, 0.1, 0.1]);
+
+        document["objects"] = json!([
+            { "type": "quad", "q": [0, 0, 0], "u": [1, 0, 0], "v": [0, 1, 0], "material": "lamp" },
+            { "type": "xz_rect", "x": [0, 1], "z": [0, 1], "y": 2, "material": "red" },
+            { "type": "plane", "point": [0, -1, 0], "normal": [0, 1, 0], "material": "red" },
+            { "type": "box", "min": [0, 0, 0], "max": [1, 2, 3], "material": "red" }
+        ]);
+        assert!(parse_scene(&document, Path::new("")).is_ok());
+        document["objects"][3]["transform"] = json!([
+            { "scale": [1, 2, 1] },
+            { "rotate": { "axis": [0, 1, 0], "angle": 30 } },
+            { "matrix": [[1, 0, 0, 5], [0, 1, 0, 0], [0, 0, 1, 0]] }
+        ]);
+        assert!(parse_scene(&document, Path::new("")).is_ok());
+        document["objects"][3]["transform"] = json!([{ "shear": 1 }]);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[3].transform[0]: unknown transform 'shear' (expected translate, scale, rotate_x, rotate_y, rotate_z, rotate or matrix)"
+        );
+        document["objects"][3]["transform"] =
+            json!([{ "matrix": [[0, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]] }]);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[3].transform[0].matrix: the matrix must be affine (last row 0 0 0 1) and invertible"
+        );
+        document["objects"][3]
+            .as_object_mut()
+            .unwrap()
+            .remove("transform");
+        document["objects"].as_array_mut().unwrap().push(json!({
+            "type": "constant_medium",
+            "boundary": { "type": "sphere", "center": [0, 0, 0], "radius": 2 },
+            "density": 0.5,
+            "albedo": [1, 1, 1]
+        }));
+        assert!(parse_scene(&document, Path::new("")).is_ok());
+        document["objects"][4]["boundary"] =
+            json!({ "type": "plane", "point": [0, 0, 0], "normal": [0, 1, 0] });
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[4].boundary: the boundary must be a closed shape"
+        );
+        document["objects"][4]["boundary"]["type"] = json!("box");
+        document["objects"][4]["density"] = json!(0);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[4].density: must be positive"
+        );
+        document["objects"][4] = json!({
+            "type": "heterogeneous_medium",
+            "boundary": { "type": "box", "min": [0, 0, 0], "max": [2, 1, 1] },
+            "grid": { "type": "noise", "resolution": [8, 4, 4], "scale": 2, "seed": 3 },
+            "absorption": 0.5,
+            "scattering": 4,
+            "emission": [1, 0.5, 0],
+            "g": 0.6
+        });
+        assert!(parse_scene(&document, Path::new("")).is_ok());
+        document["objects"][4]["grid"] =
+            json!({ "type": "raw", "file": "missing.raw", "resolution": [8, 0, 4] });
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[4].grid.resolution[1]: must be at least 1"
+        );
+        document["objects"][4]["grid"]["resolution"][1] = json!(4);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[4].grid.file: No such file or directory (os error 2)"
+        );
+        document["objects"][4]["grid"] = json!({ "type": "noise", "resolution": [4, 4, 4] });
+        document["objects"][4]["absorption"] = json!(0);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[4].emission: only an absorbing medium can emit"
+        );
+        document["objects"].as_array_mut().unwrap().pop();
+        document["objects"][1]["z"] = json!([1, 0]);
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[1].z: the range must not be empty"
+        );
+        document["objects"][1] = json!({ "type": "box", "min": [0, 0, 0], "max": [1, 0, 1] });
+        assert_eq!(
+            error_of(document.clone()),
+            "objects[1]: 'min' and 'max' must differ on every axis"
+        );
+        let obj: PathBuf =
+            std::env::temp_dir().join(format!("no_faces_{}.obj", std::process::id()));
+        std::fs::write(&obj, "v 0 0 0\n").unwrap();
+        document["objects"][1] = json!({ "type": "mesh", "file": obj, "material": "red" });
+        let untransformed: String = error_of(document.clone());
+        document["objects"][1]["transform"] = json!([{ "translate": [1, 0, 0] }]);
+        let transformed: String = error_of(document.clone());
+        std::fs::remove_file(&obj).unwrap();
+        assert_eq!(untransformed, "objects[1].file: contains no faces");
+        assert_eq!(transformed, untransformed);
+
+        document["background"] = json!("night");
+        assert_eq!(
+            error_of(document),
+            "background: unknown background 'night' (expected \"sky\", a color or an object)"
+        );
+    }
+
+    #[test]
+    fn test_parse_environments() {
+        let mut document: Value = minimal();
+        document["background"] = json!({ "type": "gradient", "top": [0, 0, 1] });
+        let scene: Scene = parse_scene(&document, Path::new("")).unwrap();
+        assert_eq!(
+            scene.environment.color(&Vec3::new(0.0, 1.0, 0.0)),
+            Color::new(0.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            scene.environment.color(&Vec3::new(0.0, -1.0, 0.0)),
+            Color::new(1.0, 1.0, 1.0)
+        );
+
+        document["background"] =
+            json!({ "type": "equirect", "file": "missing.hdr", "intensity": -1 });
+        assert_eq!(
+            error_of(document.clone()),
+            "background.intensity: must not be negative"
+        );
+        document["background"]["intensity"] = json!(2);
+        assert!(error_of(document.clone()).starts_with("background.file: missing.hdr: "));
+
+        document["background"] = json!({ "type": "physical_sky", "elevation": 30, "azimuth": 90 });
+        let scene: Scene = parse_scene(&document, Path::new("")).unwrap();
+        let sun: Vec3 = Vec3::new(0.866, 0.5, 0.0);
+        assert!(scene.environment.color(&sun).luminance() > 1000.0);
+        assert!(scene.environment.pdf_value(&sun) > 1000.0);
+        document["background"]["turbidity"] = json!(12);
+        assert_eq!(
+            error_of(document.clone()),
+            "background.turbidity: must lie between 2 and 10"
+        );
+
+        document["background"] = json!({ "type": "starfield" });
+        assert_eq!(
+            error_of(document),
+            "background.type: unknown background type 'starfield' (expected gradient, equirect or physical_sky)"
+        );
+    }
+
+    #[test]
+    fn test_unknown_material_path() {
+        let mut document: Value = minimal();
+        document["objects"][1]["material"] = json!("steel");
+        assert_eq!(
+            error_of(document),
+            "objects[1].material: unknown material 'steel'"
+        );
+    }
+
+    #[test]
+    fn test_type_errors_path() {
+        let mut document: Value = minimal();
+        document["objects"][0]["center"] = json!([0, "up", 0]);
+        assert_eq!(
+            error_of(document),
+            "objects[0].center[1]: expected a number"
+        );
+
+        let mut document: Value = minimal();
+        document["materials"]["red"]["albedo"] = json!([1, 1]);
+        assert_eq!(
+            error_of(document),
+            "materials.red.albedo: expected 3 numbers, found 2"
+        );
+
+        let mut document: Value = minimal();
+        document["render"]["samples_per_pixel"] = json!(0);
+        assert_eq!(
+            error_of(document),
+            "render.samples_per_pixel: must be at least 1"
+        );
+
+        let mut document: Value = minimal();
+        document["render"]["mis"] = json!("power");
+        assert_eq!(
+            parse_scene(&document, Path::new("")).unwrap().settings.mis,
+            MisHeuristic::Power
+        );
+        document["render"]["mis"] = json!("maximum");
+        assert_eq!(
+            error_of(document.clone()),
+            "render.mis: unknown heuristic 'maximum' (expected balance or power)"
+        );
+
+        let mut document: Value = minimal();
+        document["render"]["adaptive"] = json!({ "threshold": 0.02 });
+        assert_eq!(
+            parse_scene(&document, Path::new(""))
+                .unwrap()
+                .settings
+                .adaptive,
+            Some(AdaptiveSettings {
+                threshold: 0.02,
+                max_samples: 32
+            })
+        );
+        document["render"]["adaptive"]["max_samples_per_pixel"] = json!(2);
+        assert_eq!(
+            error_of(document.clone()),
+            "render.adaptive.max_samples_per_pixel: must be at least samples_per_pixel (4)"
+        );
+        document["render"]["adaptive"] = json!({ "threshold": 0 });
+        assert_eq!(
+            error_of(document.clone()),
+            "render.adaptive.threshold: must be a positive number"
+        );
+        // Too large for an f32, so it would never stop a pixel early.
+        document["render"]["adaptive"] = json!({ "threshold": 1e300 });
+        assert_eq!(
+            error_of(document.clone()),
+            "render.adaptive.threshold: must be a positive number"
+        );
+
+        let mut document: Value = minimal();
+        document["render"]["sampler"] = json!("sobol");
+        assert_eq!(
+            parse_scene(&document, Path::new(""))
+                .unwrap()
+                .settings
+                .sampler,
+            SamplerKind::Sobol
+        );
+        document["render"]["sampler"] = json!("random");
+        assert_eq!(
+            error_of(document.clone()),
+            "render.sampler: unknown sampler 'random' (expected independent, stratified, halton or sobol)"
+        );
+
+        let mut document: Value = minimal();
+        document["render"]["min_bounces"] = json!(5);
+        assert_eq!(
+            parse_scene(&document, Path::new(""))
+                .unwrap()
+                .settings
+                .path()
+                .min_bounces,
+            5
+        );
+        document["render"]["min_bounces"] = json!(-1);
+        assert_eq!(
+            error_of(document),
+            "render.min_bounces: expected an integer between 0 and 65535"
+        );
+    }
+
+    #[test]
+    fn test_missing_and_unknown_fields() {
+        let mut document: Value = minimal();
+        document.as_object_mut().unwrap().remove("camera");
+        assert_eq!(error_of(document), "<root>: missing field 'camera'");
+
+        let mut document: Value = minimal();
+        document["objects"][0]["radus"] = json!(2);
+        assert_eq!(error_of(document), "objects[0]: unknown field 'radus'");
+
+        let mut document: Value = minimal();
+        document["materials"]["red"]["albedo"] =
+            json!({ "type": "image", "file": "missing.png", "wrap": "tile" });
+        assert_eq!(
+            error_of(document.clone()),
+            "materials.red.albedo.wrap: unknown wrap mode 'tile' (expected repeat, mirror or clamp)"
+        );
+
+        let mut document: Value = minimal();
+        document["objects"][0]["type"] = json!("cube");
+        assert_eq!(
+            error_of(document),
+            "objects[0].type: unknown object type 'cube' (expected sphere, moving_sphere, triangle, mesh, quad, xy_rect, xz_rect, yz_rect, plane, box, constant_medium or heterogeneous_medium)"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use super::*;
+use serde_json::Value;
+
+/// A value inside the scene document together with its path from the root,
+/// e.g. `objects[3].center`, so that every validation error can say where
+/// in the file it happened.
+pub struct Node<'a> {
+    value: &'a Value,
+    path: String,
+}
+
+impl<'a> Node<'a> {
+    pub fn root(value: &'a Value) -> Self {
+        Self {
+            value,
+            path: String::new(),
+        }
+    }
+
+    pub fn error<T>(&self, message: String) -> Result<T, SceneError> {
+        Err(SceneError::Invalid {
+            path: if self.path.is_empty() {
+                "<root>".to_string()
+            } else {
+                self.path.clone()
+            },
+            message,
+        })
+    }
+
+    pub fn opt(&self, key: &str) -> Option<Node<'a>> {
+        self.value.get(key).map(|value| Node {
+            value,
+            path: if self.path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", self.path, key)
+            },
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Result<Node<'a>, SceneError> {
+        match self.opt(key) {
+            Some(node) => Ok(node),
+            None => self.error(format!("missing field '{}'", key)),
+        }
+    }
+
+    /// Rejects any key not in `allowed`, which catches misspelled fields that
+    /// would otherwise be silently replaced by their defaults.
+    pub fn check_fields(&self, allowed: &[&str]) -> Result<(), SceneError> {
+        for (key, _) in self.as_object()? {
+            if !allowed.contains(&key) {
+                return self.error(format!("unknown field '{}'", key));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn as_object(&self) -> Result<Vec<(&'a str, Node<'a>)>, SceneError> {
+        match self.value.as_object() {
+            Some(map) => Ok(map
+                .keys()
+                .map(|key| (key.as_str(), self.opt(key).unwrap()))
+                .collect()),
+            None => self.error("expected an object".to_string()),
+        }
+    }
+
+    pub fn as_array(&self) -> Result<Vec<Node<'a>>, SceneError> {
+        match self.value.as_array() {
+            Some(items) => Ok(items
+                .iter()
+                .enumerate()
+                .map(|(i, value)| Node {
+                    value,
+                    path: format!("{}[{}]", self.path, i),
+                })
+                .collect()),
+            None => self.error("expected an array".to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&'a str, SceneError> {
+        match self.value.as_str() {
+            Some(s) => Ok(s),
+            None => self.error("expected a string".to_string()),
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, SceneError> {
+        match self.value.as_bool() {
+            Some(b) => Ok(b),
+            None => self.error("expected true or false".to_string()),
+        }
+    }
+
+    pub fn as_f32(&self) -> Result<f32, SceneError> {
+        match self.value.as_f64() {
+            Some(x) => Ok(x as f32),
+            None => self.error("expected a number".to_string()),
+        }
+    }
+
+    pub fn as_u16(&self) -> Result<u16, SceneError> {
+        match self.value.as_u64() {
+            Some(x) if x <= u16::MAX as u64 => Ok(x as u16),
+            _ => self.error(format!("expected an integer between 0 and {}", u16::MAX)),
+        }
+    }
+
+    pub fn as_usize(&self) -> Result<usize, SceneError> {
+        match self.value.as_u64() {
+            Some(x) => Ok(x as usize),
+            None => self.error("expected a non-negative integer".to_string()),
+        }
+    }
+
+    pub fn as_vec3(&self) -> Result<Vec3, SceneError> {
+        let items: Vec<Node> = self.as_array()?;
+        if items.len() != 3 {
+            return self.error(format!("expected 3 numbers, found {}", items.len()));
+        }
+        Ok(Vec3::new(
+            items[0].as_f32()?,
+            items[1].as_f32()?,
+            items[2].as_f32()?,
+        ))
+    }
+
+    pub fn f32_or(&self, key: &str, default: f32) -> Result<f32, SceneError> {
+        self.opt(key).map_or(Ok(default), |n| n.as_f32())
+    }
+
+    pub fn vec3_or(&self, key: &str, default: Vec3) -> Result<Vec3, SceneError> {
+        self.opt(key).map_or(Ok(default), |n| n.as_vec3())
+    }
+}
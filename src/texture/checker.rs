@@ -0,0 +1,59 @@
+use super::*;
+
+/// A solid 3D checkerboard of cubes `scale` wide, alternating between the
+/// `even` and `odd` textures.
+pub struct Checker {
+    inv_scale: f32,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn new(scale: f32, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: f32, even: Color, odd: Color) -> Self {
+        Checker::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        let cell = |c: f32| (c * self.inv_scale).floor() as i64;
+        if (cell(p.x()) + cell(p.y()) + cell(p.z())) % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alternates() {
+        let white: Color = Color::new(1.0, 1.0, 1.0);
+        let black: Color = Color::new(0.0, 0.0, 0.0);
+        let checker: Checker = Checker::from_colors(0.5, white, black);
+        assert_eq!(checker.value(0.0, 0.0, &Point3::new(0.1, 0.1, 0.1)), white);
+        assert_eq!(checker.value(0.0, 0.0, &Point3::new(0.6, 0.1, 0.1)), black);
+        assert_eq!(checker.value(0.0, 0.0, &Point3::new(0.6, 0.6, 0.1)), white);
+        // Negative coordinates continue the pattern across zero.
+        assert_eq!(checker.value(0.0, 0.0, &Point3::new(-0.1, 0.1, 0.1)), black);
+    }
+}
@@ -0,0 +1,123 @@
+use super::*;
+use crate::image::{self, Framebuffer};
+
+use std::io;
+use std::path::Path;
+
+/// How texture coordinates outside [0, 1] are brought back onto the image.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WrapMode {
+    /// Tile the image.
+    #[default]
+    Repeat,
+    /// Tile the image, flipping every other copy so edges line up.
+    Mirror,
+    /// Stretch the border pixels.
+    Clamp,
+}
+
+impl WrapMode {
+    /// Maps a texel index, possibly outside the image, onto `0..size`.
+    fn wrap(&self, i: i64, size: usize) -> usize {
+        let n: i64 = size as i64;
+        match self {
+            WrapMode::Repeat => i.rem_euclid(n) as usize,
+            WrapMode::Mirror => {
+                let i: i64 = i.rem_euclid(2 * n);
+                (if i < n { i } else { 2 * n - 1 - i }) as usize
+            }
+            WrapMode::Clamp => i.clamp(0, n - 1) as usize,
+        }
+    }
+}
+
+/// An image mapped over (u, v), with v = 0 at the bottom row, filtered
+/// bilinearly between texel centers.
+pub struct ImageTexture {
+    image: Framebuffer,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    pub fn new(image: Framebuffer, wrap: WrapMode) -> Self {
+        assert!(
+            image.width() > 0 && image.height() > 0,
+            "ImageTexture needs at least one pixel."
+        );
+        Self { image, wrap }
+    }
+
+    /// Loads an image file with `image::load`.
+    pub fn load(path: &Path, wrap: WrapMode) -> io::Result<Self> {
+        let image: Framebuffer = image::load(path)?;
+        if image.width() == 0 || image.height() == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "image has no pixels",
+            ));
+        }
+        Ok(ImageTexture::new(image, wrap))
+    }
+
+    fn texel(&self, i: i64, j: i64) -> Color {
+        self.image.get(
+            self.wrap.wrap(i, self.image.width()),
+            self.wrap.wrap(j, self.image.height()),
+        )
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _p: &Point3) -> Color {
+        let x: f32 = u * self.image.width() as f32 - 0.5;
+        let y: f32 = (1.0 - v) * self.image.height() as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (i, j) = (x0 as i64, y0 as i64);
+
+        let top: Color = (1.0 - fx) * self.texel(i, j) + fx * self.texel(i + 1, j);
+        let bottom: Color = (1.0 - fx) * self.texel(i, j + 1) + fx * self.texel(i + 1, j + 1);
+        (1.0 - fy) * top + fy * bottom
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x1 image: black on the left, white on the right.
+    fn ramp(wrap: WrapMode) -> ImageTexture {
+        let pixels: Vec<Color> = vec![Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0)];
+        ImageTexture::new(Framebuffer::from_pixels(2, 1, pixels), wrap)
+    }
+
+    #[test]
+    fn test_bilinear_between_texel_centers() {
+        let texture: ImageTexture = ramp(WrapMode::Clamp);
+        let p: Point3 = Point3::default();
+        assert_eq!(texture.value(0.25, 0.5, &p).x(), 0.0);
+        assert_eq!(texture.value(0.75, 0.5, &p).x(), 1.0);
+        assert_eq!(texture.value(0.5, 0.5, &p).x(), 0.5);
+        // Clamping holds the border color past the texel centers.
+        assert_eq!(texture.value(0.0, 0.5, &p).x(), 0.0);
+        assert_eq!(texture.value(1.5, 0.5, &p).x(), 1.0);
+    }
+
+    #[test]
+    fn test_wrap_modes() {
+        assert_eq!(WrapMode::Repeat.wrap(-1, 4), 3);
+        assert_eq!(WrapMode::Repeat.wrap(5, 4), 1);
+        assert_eq!(WrapMode::Mirror.wrap(4, 4), 3);
+        assert_eq!(WrapMode::Mirror.wrap(-1, 4), 0);
+        assert_eq!(WrapMode::Clamp.wrap(-3, 4), 0);
+        assert_eq!(WrapMode::Clamp.wrap(9, 4), 3);
+
+        // Repeating blends the right edge back into the left one.
+        let texture: ImageTexture = ramp(WrapMode::Repeat);
+        assert_eq!(texture.value(0.0, 0.5, &Point3::default()).x(), 0.5);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::vec3::*;
+
+use std::sync::Arc;
+
+/// A color that varies over a surface, looked up by the hit's texture
+/// coordinates `(u, v)` or by the hit point `p` itself.
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color;
+}
+
+pub mod checker;
+pub mod image_texture;
+pub mod noise;
+pub mod solid_color;
+
+pub use checker::Checker;
+pub use image_texture::{ImageTexture, WrapMode};
+pub use noise::{NoisePattern, NoiseTexture};
+pub use solid_color::SolidColor;
@@ -0,0 +1,111 @@
+use super::*;
+use crate::rtweekend::Perlin;
+
+/// How `NoiseTexture` turns Perlin noise into a blend factor between its
+/// two colors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoisePattern {
+    /// Raw turbulence.
+    Turbulence,
+    /// Sine stripes along z, distorted by turbulence into veins.
+    Marble,
+    /// Rings around the y axis, warped by fBm.
+    Wood,
+    /// Soft fBm blotches.
+    Cloud,
+}
+
+impl NoisePattern {
+    /// The colors blended from where the pattern is 0 to where it is 1.
+    fn default_colors(&self) -> (Color, Color) {
+        match self {
+            NoisePattern::Turbulence => (Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0)),
+            NoisePattern::Marble => (Color::new(0.1, 0.1, 0.12), Color::new(0.95, 0.95, 0.95)),
+            NoisePattern::Wood => (Color::new(0.76, 0.55, 0.3), Color::new(0.45, 0.26, 0.12)),
+            NoisePattern::Cloud => (Color::new(0.3, 0.5, 0.9), Color::new(1.0, 1.0, 1.0)),
+        }
+    }
+}
+
+/// A procedural solid texture summing `octaves` layers of noise. `scale` is the
+/// noise frequency for turbulence and clouds, and the stripe or ring frequency
+/// for marble and wood, whose distortion stays at the base frequency.
+pub struct NoiseTexture {
+    noise: Perlin,
+    pattern: NoisePattern,
+    scale: f32,
+    octaves: u32,
+    low: Color,
+    high: Color,
+}
+
+impl NoiseTexture {
+    pub fn new(pattern: NoisePattern, seed: u64, scale: f32, octaves: u32) -> Self {
+        let (low, high) = pattern.default_colors();
+        Self {
+            noise: Perlin::new(seed),
+            pattern,
+            scale,
+            octaves,
+            low,
+            high,
+        }
+    }
+
+    pub fn with_colors(mut self, low: Color, high: Color) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+
+    /// The pattern's blend factor in [0, 1].
+    fn pattern_value(&self, p: &Point3) -> f32 {
+        let q: Point3 = self.scale * *p;
+        let t: f32 = match self.pattern {
+            NoisePattern::Turbulence => self.noise.turbulence(&q, self.octaves),
+            NoisePattern::Marble => {
+                0.5 * (1.0 + (q.z() + 10.0 * self.noise.turbulence(p, self.octaves)).sin())
+            }
+            NoisePattern::Wood => {
+                let r: f32 = (q.x() * q.x() + q.z() * q.z()).sqrt();
+                (r + 2.0 * self.noise.fbm(p, self.octaves)).rem_euclid(1.0)
+            }
+            NoisePattern::Cloud => 0.5 + self.noise.fbm(&q, self.octaves),
+        };
+        t.clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Point3) -> Color {
+        let t: f32 = self.pattern_value(p);
+        (1.0 - t) * self.low + t * self.high
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_stay_between_colors() {
+        for pattern in [
+            NoisePattern::Turbulence,
+            NoisePattern::Marble,
+            NoisePattern::Wood,
+            NoisePattern::Cloud,
+        ] {
+            let texture: NoiseTexture = NoiseTexture::new(pattern, 3, 4.0, 7)
+                .with_colors(Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0));
+            for i in 0..200 {
+                let t: f32 = i as f32 * 0.071;
+                let c: Color = texture.value(0.0, 0.0, &Point3::new(t, 1.0 - t, 0.3 * t));
+                assert!((0.0..=1.0).contains(&c.x()), "{:?}: {:?}", pattern, c);
+            }
+        }
+    }
+}
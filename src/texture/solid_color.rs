@@ -0,0 +1,17 @@
+use super::*;
+
+pub struct SolidColor {
+    color_value: Color,
+}
+
+impl SolidColor {
+    pub fn new(c: Color) -> Self {
+        Self { color_value: c }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        self.color_value
+    }
+}
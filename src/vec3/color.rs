@@ -1,22 +1,60 @@
 use super::*;
-use std::fs::File;
-use std::io::prelude::*;
 
-pub const COLOR_RANGE: u16 = 256;
+pub const COLOR_RANGE: u16 = 255;
 
 impl Color {
-    pub fn write_to_file(&self, file: &mut File, sample_per_pixel: u16) -> std::io::Result<()> {
-        let scale = 1.0 / sample_per_pixel as f32;
-        let r = (self.x() * scale).sqrt();
-        let g = (self.y() * scale).sqrt();
-        let b = (self.z() * scale).sqrt();
-
-        file.write_fmt(format_args!(
-            "{} {} {}\n",
-            (COLOR_RANGE as f32 * clamp(r, 0.0, 1.0)) as u16,
-            (COLOR_RANGE as f32 * clamp(g, 0.0, 1.0)) as u16,
-            (COLOR_RANGE as f32 * clamp(b, 0.0, 1.0)) as u16
-        ))?;
-        Ok(())
+    /// Encodes linear radiance with the sRGB transfer curve, clamping anything
+    /// outside [0, 1], as used by every 8-bit image format we write.
+    pub fn to_srgb8(&self) -> [u8; 3] {
+        let encode = |c: f32| -> u8 {
+            let c: f32 = clamp(c, 0.0, 1.0);
+            let s: f32 = if c <= 0.003_130_8 {
+                12.92 * c
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+            (s * COLOR_RANGE as f32 + 0.5) as u8
+        };
+        [encode(self.x()), encode(self.y()), encode(self.z())]
+    }
+
+    /// Inverse of `to_srgb8` for sRGB-encoded channels in [0, 1].
+    pub fn from_srgb(r: f32, g: f32, b: f32) -> Color {
+        let decode = |c: f32| -> f32 {
+            if c <= 0.040_45 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Color::new(decode(r), decode(g), decode(b))
+    }
+
+    /// Perceived brightness of linear Rec. 709 radiance.
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.x() + 0.7152 * self.y() + 0.0722 * self.z()
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_srgb8() {
+        assert_eq!(Color::new(0.0, 1.0, 2.0).to_srgb8(), [0, 255, 255]);
+        assert_eq!(Color::new(-1.0, 0.5, 0.002).to_srgb8(), [0, 188, 7]);
+    }
+
+    #[test]
+    fn test_from_srgb_round_trip() {
+        for c in [0u8, 7, 100, 188, 255] {
+            let s: f32 = c as f32 / COLOR_RANGE as f32;
+            assert_eq!(Color::from_srgb(s, s, s).to_srgb8(), [c, c, c]);
+        }
     }
 }
@@ -36,36 +36,62 @@ impl Vec3 {
         self.z
     }
 
-    pub fn random_with_range(min: f32, max: f32) -> Vec3 {
+    pub fn random_with_range(min: f32, max: f32, rng: &mut Rng) -> Vec3 {
         Vec3::new(
-            random_double_with_range(min, max),
-            random_double_with_range(min, max),
-            random_double_with_range(min, max),
+            rng.random_double_with_range(min, max),
+            rng.random_double_with_range(min, max),
+            rng.random_double_with_range(min, max),
         )
     }
 
-    pub fn random() -> Vec3 {
-        Vec3::random_with_range(0.0, 1.0)
+    pub fn random(rng: &mut Rng) -> Vec3 {
+        Vec3::random_with_range(0.0, 1.0, rng)
     }
 
-    pub fn random_in_unit_sphere() -> Vec3 {
+    pub fn random_in_unit_sphere(rng: &mut Rng) -> Vec3 {
         loop {
-            let p: Vec3 = Vec3::random();
+            let p: Vec3 = Vec3::random_with_range(-1.0, 1.0, rng);
             if p.length_squared() <= 1.0 {
                 return p;
             }
         }
     }
 
-    pub fn random_unit_vector() -> Vec3 {
-        let a = random_double_with_range(0.0, 2.0 * PI);
-        let z = random_double_with_range(-1.0, 1.0);
-        let r = (1.0 - z * z).sqrt();
-        Vec3::new(r * a.cos(), r * a.sin(), z)
+    pub fn random_unit_vector(rng: &mut Rng) -> Vec3 {
+        Vec3::sample_unit_vector((rng.random_double(), rng.random_double()))
     }
 
-    pub fn random_in_hemisphere(normal: &Vec3) -> Vec3 {
-        let in_unit_sphere = Color::random_in_unit_sphere();
+    /// The unit vector that the point `u` of the unit square maps to, so that
+    /// uniform `u` covers the sphere uniformly.
+    pub fn sample_unit_vector(u: (f32, f32)) -> Vec3 {
+        let z: f32 = 1.0 - 2.0 * u.0;
+        let phi: f32 = 2.0 * PI * u.1;
+        let r: f32 = (1.0 - z * z).max(0.0).sqrt();
+        Vec3::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    /// A unit vector about +z whose density is proportional to its z component,
+    /// for a uniform `u`.
+    pub fn sample_cosine_direction(u: (f32, f32)) -> Vec3 {
+        let phi: f32 = 2.0 * PI * u.0;
+        Vec3::new(
+            phi.cos() * u.1.sqrt(),
+            phi.sin() * u.1.sqrt(),
+            (1.0 - u.1).sqrt(),
+        )
+    }
+
+    /// A unit vector about +z spread uniformly over the directions at most
+    /// `acos(cos_theta_max)` away from it, for a uniform `u`.
+    pub fn sample_in_cone(cos_theta_max: f32, u: (f32, f32)) -> Vec3 {
+        let z: f32 = 1.0 + u.0 * (cos_theta_max - 1.0);
+        let phi: f32 = 2.0 * PI * u.1;
+        let sin_theta: f32 = (1.0 - z * z).max(0.0).sqrt();
+        Vec3::new(phi.cos() * sin_theta, phi.sin() * sin_theta, z)
+    }
+
+    pub fn random_in_hemisphere(normal: &Vec3, rng: &mut Rng) -> Vec3 {
+        let in_unit_sphere = Color::random_in_unit_sphere(rng);
         if in_unit_sphere.dot(normal) > 0.0 {
             in_unit_sphere
         } else {
@@ -73,22 +99,24 @@ impl Vec3 {
         }
     }
 
-    pub fn random_in_unit_disk() -> Vec3 {
-        loop {
-            let p = Vec3::new(
-                random_double_with_range(-1.0, 1.0),
-                random_double_with_range(-1.0, 1.0),
-                0.0,
-            );
-            if p.length_squared() >= 1.0 {
-                continue;
-            }
-            return p;
+    /// A point in the unit disk of the xy plane, uniform for a uniform `u`.
+    /// Shirley and Chiu's concentric mapping keeps neighbouring samples close,
+    /// so stratified samples stay stratified.
+    pub fn sample_in_unit_disk(u: (f32, f32)) -> Vec3 {
+        let (a, b) = (2.0 * u.0 - 1.0, 2.0 * u.1 - 1.0);
+        if a == 0.0 && b == 0.0 {
+            return Vec3::new(0.0, 0.0, 0.0);
         }
+        let (r, theta) = if a.abs() > b.abs() {
+            (a, PI / 4.0 * (b / a))
+        } else {
+            (b, PI / 2.0 - PI / 4.0 * (a / b))
+        };
+        Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
     }
 
     pub fn reflect(u: &Vec3, n: &Vec3) -> Vec3 {
-        *u - 2.0 * u.dot(&n) * *n
+        *u - 2.0 * u.dot(n) * *n
     }
 
     pub fn refract(uv: &Vec3, n: &Vec3, etai_over_etat: f32) -> Vec3 {
@@ -183,8 +211,13 @@ impl DivAssign<f32> for Vec3 {
 }
 
 pub mod color;
+pub mod onb;
+pub mod transform;
 pub mod vector;
 
+pub use onb::Onb;
+pub use transform::Transform;
+
 /**************************
  *   Unit Test
  **************************/
@@ -282,4 +315,18 @@ mod tests {
         let v1: Point3 = Point3::new(x1, y1, z1);
         assert_eq!(v1.length(), 7.071068f32);
     }
+
+    #[test]
+    fn test_random_in_unit_sphere_covers_every_octant() {
+        let mut rng: Rng = Rng::new(7);
+        let mut octants: [usize; 8] = [0; 8];
+        for _ in 0..8000 {
+            let p: Vec3 = Vec3::random_in_unit_sphere(&mut rng);
+            assert!(p.length_squared() <= 1.0);
+            let octant: usize =
+                (p.x() < 0.0) as usize + 2 * (p.y() < 0.0) as usize + 4 * (p.z() < 0.0) as usize;
+            octants[octant] += 1;
+        }
+        assert!(octants.iter().all(|&n| n > 800), "{:?}", octants);
+    }
 }
@@ -0,0 +1,33 @@
+use super::*;
+
+/// An orthonormal basis whose `w` axis points along a given direction, for
+/// turning directions sampled around the z axis into world space.
+#[derive(Clone, Copy, Debug)]
+pub struct Onb {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Onb {
+    pub fn build_from_w(n: &Vec3) -> Self {
+        let w: Vec3 = n.unit_vector();
+        let a: Vec3 = if w.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v: Vec3 = w.cross(&a).unit_vector();
+        let u: Vec3 = w.cross(&v);
+        Self { u, v, w }
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.w
+    }
+
+    /// The world-space vector with coordinates `a` in this basis.
+    pub fn local(&self, a: &Vec3) -> Vec3 {
+        a.x() * self.u + a.y() * self.v + a.z() * self.w
+    }
+}
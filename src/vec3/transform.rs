@@ -0,0 +1,278 @@
+use super::*;
+use std::ops::Mul;
+
+type Matrix = [[f32; 4]; 4];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// A 4x4 affine transform together with its inverse. Composition follows
+/// matrix order: `a * b` applies `b` first, then `a`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    m: Matrix,
+    inv: Matrix,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            m: IDENTITY,
+            inv: IDENTITY,
+        }
+    }
+
+    /// Accepts a row-major matrix whose last row is `0 0 0 1`. Returns `None`
+    /// if it is not affine or cannot be inverted.
+    pub fn from_matrix(m: Matrix) -> Option<Self> {
+        if m[3] != [0.0, 0.0, 0.0, 1.0] {
+            return None;
+        }
+        // Invert the linear 3x3 part by cofactors, then undo the translation.
+        let c = |r: usize, c: usize| m[r][c];
+        let cof: [[f32; 3]; 3] = [
+            [
+                c(1, 1) * c(2, 2) - c(1, 2) * c(2, 1),
+                c(1, 2) * c(2, 0) - c(1, 0) * c(2, 2),
+                c(1, 0) * c(2, 1) - c(1, 1) * c(2, 0),
+            ],
+            [
+                c(0, 2) * c(2, 1) - c(0, 1) * c(2, 2),
+                c(0, 0) * c(2, 2) - c(0, 2) * c(2, 0),
+                c(0, 1) * c(2, 0) - c(0, 0) * c(2, 1),
+            ],
+            [
+                c(0, 1) * c(1, 2) - c(0, 2) * c(1, 1),
+                c(0, 2) * c(1, 0) - c(0, 0) * c(1, 2),
+                c(0, 0) * c(1, 1) - c(0, 1) * c(1, 0),
+            ],
+        ];
+        let det: f32 = c(0, 0) * cof[0][0] + c(0, 1) * cof[0][1] + c(0, 2) * cof[0][2];
+        if det.abs() < 1e-12 || !det.is_finite() {
+            return None;
+        }
+
+        let mut inv: Matrix = IDENTITY;
+        for (i, row) in inv.iter_mut().take(3).enumerate() {
+            for (j, entry) in row.iter_mut().take(3).enumerate() {
+                // The inverse is the transposed cofactor matrix over the determinant.
+                *entry = cof[j][i] / det;
+            }
+        }
+        for row in inv.iter_mut().take(3) {
+            row[3] = -(0..3).map(|j| row[j] * m[j][3]).sum::<f32>();
+        }
+        Some(Self { m, inv })
+    }
+
+    pub fn translate(offset: Vec3) -> Self {
+        let mut m: Matrix = IDENTITY;
+        let mut inv: Matrix = IDENTITY;
+        for a in 0..3 {
+            m[a][3] = offset[a];
+            inv[a][3] = -offset[a];
+        }
+        Self { m, inv }
+    }
+
+    /// Scales by `factors` along each axis; none of them may be zero.
+    pub fn scale(factors: Vec3) -> Self {
+        assert!(
+            factors.x() != 0.0 && factors.y() != 0.0 && factors.z() != 0.0,
+            "Scale factors must not be zero."
+        );
+        let mut m: Matrix = IDENTITY;
+        let mut inv: Matrix = IDENTITY;
+        for a in 0..3 {
+            m[a][a] = factors[a];
+            inv[a][a] = 1.0 / factors[a];
+        }
+        Self { m, inv }
+    }
+
+    /// Rotates counter-clockwise by `degrees` when looking down `axis`
+    /// towards the origin.
+    pub fn rotate(axis: Vec3, degrees: f32) -> Self {
+        let a: Vec3 = axis.unit_vector();
+        let (sin, cos) = degree_to_radians(degrees).sin_cos();
+        let t: f32 = 1.0 - cos;
+        let r: [[f32; 3]; 3] = [
+            [
+                t * a.x() * a.x() + cos,
+                t * a.x() * a.y() - sin * a.z(),
+                t * a.x() * a.z() + sin * a.y(),
+            ],
+            [
+                t * a.x() * a.y() + sin * a.z(),
+                t * a.y() * a.y() + cos,
+                t * a.y() * a.z() - sin * a.x(),
+            ],
+            [
+                t * a.x() * a.z() - sin * a.y(),
+                t * a.y() * a.z() + sin * a.x(),
+                t * a.z() * a.z() + cos,
+            ],
+        ];
+        let mut m: Matrix = IDENTITY;
+        let mut inv: Matrix = IDENTITY;
+        for i in 0..3 {
+            for j in 0..3 {
+                m[i][j] = r[i][j];
+                // Rotations are orthogonal: the inverse is the transpose.
+                inv[j][i] = r[i][j];
+            }
+        }
+        Self { m, inv }
+    }
+
+    pub fn rotate_x(degrees: f32) -> Self {
+        Transform::rotate(Vec3::new(1.0, 0.0, 0.0), degrees)
+    }
+
+    pub fn rotate_y(degrees: f32) -> Self {
+        Transform::rotate(Vec3::new(0.0, 1.0, 0.0), degrees)
+    }
+
+    pub fn rotate_z(degrees: f32) -> Self {
+        Transform::rotate(Vec3::new(0.0, 0.0, 1.0), degrees)
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            m: self.inv,
+            inv: self.m,
+        }
+    }
+
+    pub fn matrix(&self) -> Matrix {
+        self.m
+    }
+
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        apply(&self.m, p, 1.0)
+    }
+
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        apply(&self.m, v, 0.0)
+    }
+
+    /// Normals transform by the inverse transpose so they stay perpendicular
+    /// to transformed surfaces under non-uniform scaling. The result is not
+    /// normalized.
+    pub fn transform_normal(&self, n: &Vec3) -> Vec3 {
+        let m: &Matrix = &self.inv;
+        Vec3::new(
+            m[0][0] * n.x() + m[1][0] * n.y() + m[2][0] * n.z(),
+            m[0][1] * n.x() + m[1][1] * n.y() + m[2][1] * n.z(),
+            m[0][2] * n.x() + m[1][2] * n.y() + m[2][2] * n.z(),
+        )
+    }
+}
+
+fn apply(m: &Matrix, v: &Vec3, w: f32) -> Vec3 {
+    Vec3::new(
+        m[0][0] * v.x() + m[0][1] * v.y() + m[0][2] * v.z() + m[0][3] * w,
+        m[1][0] * v.x() + m[1][1] * v.y() + m[1][2] * v.z() + m[1][3] * w,
+        m[2][0] * v.x() + m[2][1] * v.y() + m[2][2] * v.z() + m[2][3] * w,
+    )
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out: Matrix = [[0.0; 4]; 4];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Transform) -> Transform {
+        Transform {
+            m: multiply(&self.m, &rhs.m),
+            inv: multiply(&rhs.inv, &self.inv),
+        }
+    }
+}
+
+/**************************
+ * Unit Test
+ **************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_rotate_y() {
+        let t: Transform = Transform::rotate_y(90.0);
+        assert_close(
+            t.transform_point(&Point3::new(1.0, 0.0, 0.0)),
+            Point3::new(0.0, 0.0, -1.0),
+        );
+        assert_close(
+            Transform::rotate_z(90.0).transform_vector(&Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn test_compose_and_inverse() {
+        let t: Transform = Transform::translate(Vec3::new(1.0, 2.0, 3.0))
+            * Transform::rotate(Vec3::new(1.0, 1.0, 0.0), 33.0)
+            * Transform::scale(Vec3::new(2.0, 0.5, 3.0));
+        let p: Point3 = Point3::new(0.3, -1.2, 4.0);
+        // Scale, then rotate, then translate.
+        assert_close(
+            t.transform_point(&p),
+            Transform::rotate(Vec3::new(1.0, 1.0, 0.0), 33.0)
+                .transform_point(&Point3::new(0.6, -0.6, 12.0))
+                + Vec3::new(1.0, 2.0, 3.0),
+        );
+        assert_close(t.inverse().transform_point(&t.transform_point(&p)), p);
+
+        let from_matrix: Transform = Transform::from_matrix(t.matrix()).unwrap();
+        assert_close(
+            from_matrix
+                .inverse()
+                .transform_point(&t.transform_point(&p)),
+            p,
+        );
+    }
+
+    #[test]
+    fn test_from_matrix_rejects_singular() {
+        let mut m: [[f32; 4]; 4] = Transform::identity().matrix();
+        m[2][2] = 0.0;
+        assert!(Transform::from_matrix(m).is_none());
+        let mut m: [[f32; 4]; 4] = Transform::identity().matrix();
+        m[3][0] = 1.0;
+        assert!(Transform::from_matrix(m).is_none());
+    }
+
+    #[test]
+    fn test_normal_stays_perpendicular() {
+        let t: Transform = Transform::scale(Vec3::new(4.0, 1.0, 1.0));
+        // The plane x + y = 0 has normal (1, 1, 0) and contains (1, -1, 0).
+        let tangent: Vec3 = t.transform_vector(&Vec3::new(1.0, -1.0, 0.0));
+        let normal: Vec3 = t.transform_normal(&Vec3::new(1.0, 1.0, 0.0));
+        assert!(tangent.dot(&normal).abs() < 1e-6);
+    }
+}